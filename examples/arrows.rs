@@ -0,0 +1,54 @@
+use vecdraw::{
+    run_event_loop, ArrowEnds, ArrowHead, ArrowStyle, ArrowsLayer, GenericLayer, GridLayer,
+    GroupLayer, Line, WidthUnits,
+};
+
+fn create() -> GroupLayer {
+    let heads = [ArrowHead::Triangle, ArrowHead::Open, ArrowHead::Barbed];
+    let mut layers: Vec<Box<dyn GenericLayer>> = vec![Box::new(GridLayer::new(
+        5,
+        5,
+        palette::named::PALETURQUOISE.into(),
+    ))];
+
+    for (i, &head) in heads.iter().enumerate() {
+        let y = 300. - 300. * i as f32;
+        layers.push(Box::new(ArrowsLayer::new(
+            vec![Line {
+                start: [-450., y],
+                end: [-50., y + 100.],
+                color: palette::named::STEELBLUE.into(),
+                width: 5.,
+            }],
+            ArrowStyle {
+                head,
+                ends: ArrowEnds::End,
+                head_length: 60.,
+                head_width: 25.,
+            },
+        )));
+        layers.push(Box::new(
+            ArrowsLayer::new(
+                vec![Line {
+                    start: [50., y],
+                    end: [450., y],
+                    color: palette::named::SALMON.into(),
+                    width: 1.,
+                }],
+                ArrowStyle {
+                    head,
+                    ends: ArrowEnds::Both,
+                    head_length: 12.,
+                    head_width: 5.,
+                },
+            )
+            .with_width_units(WidthUnits::Pixels),
+        ));
+    }
+
+    GroupLayer::new(layers)
+}
+
+fn main() {
+    run_event_loop(create());
+}
@@ -1,6 +1,6 @@
 use vecdraw::{
-    run_event_loop, Circle, CirclesLayer, GridLayer, GroupLayer, Line, LinesLayer, Rectangle,
-    RectanglesLayer,
+    run_event_loop_with_options, Arc, ArcsLayer, Circle, CirclesLayer, Ellipse, EllipsesLayer,
+    GridLayer, GroupLayer, Line, LinesLayer, Rectangle, RectanglesLayer, RunOptions, WidthUnits,
 };
 
 fn create() -> GroupLayer {
@@ -11,33 +11,57 @@ fn create() -> GroupLayer {
                 position: [-220., -220.],
                 radius: 15.,
                 color: palette::named::PURPLE.into(),
+                ..Default::default()
             },
             Circle {
                 position: [300., 300.],
                 radius: 50.,
                 color: palette::named::SADDLEBROWN.into(),
+                ..Default::default()
             },
             Circle {
                 position: [-350., -350.],
                 radius: 70.,
                 color: palette::named::STEELBLUE.into(),
+                ..Default::default()
             },
         ])),
         Box::new(CirclesLayer::new(vec![Circle {
             position: [500., -300.],
             radius: 40.,
-            color: palette::named::VIOLET.into(),
+            color: palette::named::LAVENDER.into(),
+            stroke_width: 6.,
+            stroke_color: palette::named::VIOLET.into(),
+        }])),
+        Box::new(ArcsLayer::new(vec![Arc {
+            center: [-300., 100.],
+            color: palette::named::GOLDENROD.into(),
+            radius: 80.,
+            start_angle: std::f32::consts::FRAC_PI_4,
+            end_angle: 7. * std::f32::consts::FRAC_PI_4,
+            stroke_width: 0.,
+        }])),
+        Box::new(EllipsesLayer::new(vec![Ellipse {
+            position: [250., 450.],
+            radii: [120., 40.],
+            rotation: std::f32::consts::FRAC_PI_6,
+            color: palette::named::DARKSEAGREEN.into(),
         }])),
         Box::new(RectanglesLayer::new(vec![
             Rectangle {
                 upper_left: [-400., 400.],
                 bottom_right: [-450., 500.],
                 color: palette::named::SALMON.into(),
+                ..Default::default()
             },
             Rectangle {
                 upper_left: [10., 250.],
                 bottom_right: [50., 300.],
                 color: palette::named::MAROON.into(),
+                corner_radius: 8.,
+                rotation: std::f32::consts::FRAC_PI_8,
+                stroke_width: 2.,
+                stroke_color: palette::named::BLACK.into(),
             },
         ])),
         Box::new(LinesLayer::new(vec![
@@ -54,10 +78,25 @@ fn create() -> GroupLayer {
                 color: palette::named::TOMATO.into(),
             },
         ])),
+        Box::new(
+            LinesLayer::new(vec![Line {
+                start: [-450., 450.],
+                end: [450., 450.],
+                width: 1.,
+                color: palette::named::DIMGRAY.into(),
+            }])
+            .with_width_units(WidthUnits::Pixels),
+        ),
     ])
 }
 
 fn main() {
     let layer = create();
-    run_event_loop(layer);
+    run_event_loop_with_options(
+        layer,
+        RunOptions::new()
+            .with_title("vecdraw: basic")
+            .with_size(800, 800)
+            .with_clear_color(palette::named::IVORY.into()),
+    );
 }
@@ -1,43 +1,82 @@
-use chrono::Timelike;
+use chrono::{Local, NaiveTime, Timelike};
+use clap::Parser;
 use vecdraw::{
-    run_event_loop, Circle, CirclesLayer, CirclesLayerDrawable, DrawContext, DrawState, Drawable,
-    Layer, Line, LinesLayer, LinesLayerDrawable, UpdateState,
+    run_event_loop, Arc, ArcsLayer, ArcsLayerDrawable, Circle, CirclesLayer, CirclesLayerDrawable,
+    DrawContext, DrawState, Drawable, Layer, Line, LineCap, LinesLayer, LinesLayerDrawable,
+    Recording, UpdateState,
 };
 
-struct ClockLayer;
+#[derive(Parser)]
+struct Opts {
+    /// Render frames to this directory instead of opening a window.
+    #[clap(long)]
+    record: Option<String>,
+    #[clap(long, default_value = "60")]
+    frames: u32,
+    /// Time of day to start the clock at, as HH:MM:SS. Defaults to the
+    /// current time, or to 10:08:00 when recording.
+    #[clap(long)]
+    start: Option<NaiveTime>,
+}
+
+struct ClockLayer {
+    start: NaiveTime,
+}
 
 impl Layer for ClockLayer {
     type D = ClockApp;
 
     fn init_drawable(&self, draw_context: &DrawContext) -> Self::D {
-        ClockApp::new(draw_context)
+        ClockApp::new(draw_context, self.start)
     }
 }
 
 struct ClockApp {
+    start: NaiveTime,
     markers: CirclesLayerDrawable,
+    progress: ArcsLayerDrawable,
     hands: LinesLayerDrawable,
 }
 
 impl Drawable for ClockApp {
     fn update(&mut self, update_state: &UpdateState) {
+        let time = self.start + chrono::Duration::from_std(update_state.elapsed).unwrap();
+        self.progress.instance_buffer.update(
+            &Self::progress_data(time),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
         self.hands.instance_buffer.update(
-            &Self::hand_data(),
+            &Self::hand_data(time),
             update_state.device,
             update_state.encoder.borrow_mut(),
         );
     }
 
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         self.markers.draw(draw_state);
+        self.progress.draw(draw_state);
         self.hands.draw(draw_state);
     }
 }
 
 impl ClockApp {
-    fn hand_data() -> Vec<Line> {
-        let time = chrono::Local::now();
+    /// A ring that fills clockwise from twelve o'clock over each minute.
+    fn progress_data(time: NaiveTime) -> Vec<Arc> {
+        let second = time.second() as f32 + time.nanosecond() as f32 / 1e9;
+        let top = std::f32::consts::FRAC_PI_2;
+
+        vec![Arc {
+            center: [0., 0.],
+            color: palette::named::LIGHTSTEELBLUE.into(),
+            radius: 440.,
+            start_angle: top,
+            end_angle: top - std::f32::consts::TAU * (second / 60.),
+            stroke_width: 8.,
+        }]
+    }
 
+    fn hand_data(time: NaiveTime) -> Vec<Line> {
         let mili = time.nanosecond() as f32 / 1e9;
         let second = time.second() as f32 + mili;
 
@@ -94,7 +133,7 @@ impl ClockApp {
         vec![hour_hand, minute_hand, second_hand]
     }
 
-    fn new(draw_context: &DrawContext) -> Self {
+    fn new(draw_context: &DrawContext, start: NaiveTime) -> Self {
         let markers = CirclesLayer::new(
             (0..12)
                 .map(|h| {
@@ -105,14 +144,19 @@ impl ClockApp {
                         radius: 20.,
                         color: palette::named::DARKCYAN.into(),
                         position: [r * theta.sin(), r * theta.cos()],
+                        ..Default::default()
                     }
                 })
                 .collect(),
         );
 
-        let hands = LinesLayer::new(Self::hand_data());
+        let hands = LinesLayer::new(Self::hand_data(start)).with_cap(LineCap::Round);
+
+        let progress = ArcsLayer::new(Self::progress_data(start));
 
         ClockApp {
+            start,
+            progress: progress.init_drawable(draw_context),
             hands: hands.init_drawable(draw_context),
             markers: markers.init_drawable(draw_context),
         }
@@ -120,6 +164,23 @@ impl ClockApp {
 }
 
 fn main() {
-    let layer = ClockLayer;
-    run_event_loop(layer);
+    let opts = Opts::parse();
+
+    match opts.record {
+        Some(directory) => {
+            let start = opts
+                .start
+                .unwrap_or_else(|| NaiveTime::from_hms_opt(10, 8, 0).unwrap());
+            Recording {
+                frames: opts.frames,
+                ..Recording::default()
+            }
+            .save_pngs(&ClockLayer { start }, directory)
+            .unwrap()
+        }
+        None => {
+            let start = opts.start.unwrap_or_else(|| Local::now().time());
+            run_event_loop(ClockLayer { start })
+        }
+    }
 }
@@ -0,0 +1,33 @@
+use vecdraw::{
+    run_event_loop, Bezier, Curve, CurvesLayer, DashPattern, DashUnits, GridLayer, GroupLayer,
+};
+
+fn create() -> GroupLayer {
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(CurvesLayer::new(vec![
+            Curve {
+                bezier: Bezier::Quadratic([[-450., -300.], [-200., 400.], [50., -300.]]),
+                color: palette::named::STEELBLUE.into(),
+                width: 10.,
+            },
+            Curve {
+                bezier: Bezier::Cubic([[-50., 300.], [150., -500.], [250., 500.], [450., -300.]]),
+                color: palette::named::SALMON.into(),
+                width: 10.,
+            },
+        ])),
+        Box::new(
+            CurvesLayer::new(vec![Curve {
+                bezier: Bezier::Cubic([[-500., 450.], [-100., 650.], [100., 250.], [500., 450.]]),
+                color: palette::named::DIMGRAY.into(),
+                width: 2.,
+            }])
+            .with_dash(DashPattern::new(&[10., 5.], DashUnits::Pixels)),
+        ),
+    ])
+}
+
+fn main() {
+    run_event_loop(create());
+}
@@ -1,5 +1,19 @@
-use chrono::{Local, Timelike};
-use vecdraw::{Circle, CirclesLayer, CirclesLayerDrawable, Color, Drawable, Layer, UpdateState, run_event_loop};
+use chrono::{Local, NaiveTime, Timelike};
+use clap::Parser;
+use vecdraw::{Circle, CirclesLayer, CirclesLayerDrawable, Color, Drawable, Layer, Recording, UpdateState, run_event_loop};
+
+#[derive(Parser)]
+struct Opts {
+    /// Render frames to this directory instead of opening a window.
+    #[clap(long)]
+    record: Option<String>,
+    #[clap(long, default_value = "60")]
+    frames: u32,
+    /// Time of day to start the clock at, as HH:MM:SS. Defaults to the
+    /// current time, or to 10:08:00 when recording.
+    #[clap(long)]
+    start: Option<NaiveTime>,
+}
 
 struct IndicatorLayer {
     offset: f32,
@@ -35,6 +49,7 @@ impl Drawable for IndicatorDrawable {
                 color: self.color,
                 position: [(c % 6) as f32 * 20., self.offset + (c / 6) as f32 * 20.],
                 radius: 10.,
+                ..Default::default()
             }).collect();
 
             self.circles.instance_buffer.update(&circles, update_state.device,
@@ -43,7 +58,7 @@ impl Drawable for IndicatorDrawable {
         }
     }
 
-    fn draw<'a>(&'a self, draw_state: &vecdraw::DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &vecdraw::DrawState<'a, '_>) {
         self.circles.draw(draw_state);
     }
 }
@@ -54,7 +69,9 @@ impl IndicatorDrawable {
     }
 }
 
-struct ClockLayer;
+struct ClockLayer {
+    start: NaiveTime,
+}
 
 impl Layer for ClockLayer {
     type D = ClockDrawable;
@@ -72,7 +89,8 @@ impl Layer for ClockLayer {
             hours: IndicatorLayer {
                 color: palette::named::BLUE.into(),
                 offset: 300.,
-            }.init_drawable(draw_context)
+            }.init_drawable(draw_context),
+            start: self.start,
         }
     }
 }
@@ -81,11 +99,12 @@ struct ClockDrawable {
     seconds: IndicatorDrawable,
     minutes: IndicatorDrawable,
     hours: IndicatorDrawable,
+    start: NaiveTime,
 }
 
 impl Drawable for ClockDrawable {
     fn update(&mut self, update_state: &UpdateState) {
-        let now = Local::now();
+        let now = self.start + chrono::Duration::from_std(update_state.elapsed).unwrap();
         self.seconds.set_value(now.second());
         self.seconds.update(update_state);
 
@@ -96,7 +115,7 @@ impl Drawable for ClockDrawable {
         self.hours.update(update_state);
     }
 
-    fn draw<'a>(&'a self, draw_state: &vecdraw::DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &vecdraw::DrawState<'a, '_>) {
         self.seconds.draw(draw_state);
         self.minutes.draw(draw_state);
         self.hours.draw(draw_state);
@@ -104,6 +123,19 @@ impl Drawable for ClockDrawable {
 }
 
 fn main() {
-    let layer = ClockLayer;
-    run_event_loop(layer);
+    let opts = Opts::parse();
+
+    match opts.record {
+        Some(directory) => {
+            let start = opts.start.unwrap_or_else(|| NaiveTime::from_hms_opt(10, 8, 0).unwrap());
+            Recording {
+                frames: opts.frames,
+                ..Recording::default()
+            }.save_pngs(&ClockLayer { start }, directory).unwrap()
+        }
+        None => {
+            let start = opts.start.unwrap_or_else(|| Local::now().time());
+            run_event_loop(ClockLayer { start })
+        }
+    }
 }
\ No newline at end of file
@@ -17,12 +17,12 @@ impl Layer for GridDrawable {
     fn init_drawable(&self, draw_context: &DrawContext) -> Self::D {
         let GridDrawable(rows, cols) = *self;
         let circles: Vec<Circle> = (0..rows)
-            .into_iter()
             .flat_map(|r| {
-                (0..cols).into_iter().map(move |c| Circle {
+                (0..cols).map(move |c| Circle {
                     position: [c as f32 * 20., r as f32 * 20.],
                     radius: 10.,
                     color: palette::named::RED.into(),
+                    ..Default::default()
                 })
             })
             .collect();
@@ -0,0 +1,39 @@
+use clap::Parser;
+use vecdraw::{render_to_image, Camera, Circle, CirclesLayer, GridLayer, GroupLayer};
+
+#[derive(Parser)]
+struct Opts {
+    #[clap(default_value = "headless.png")]
+    output: String,
+    #[clap(default_value = "600")]
+    width: u32,
+    #[clap(default_value = "600")]
+    height: u32,
+}
+
+fn create() -> GroupLayer {
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(CirclesLayer::new(vec![
+            Circle {
+                position: [-220., -220.],
+                radius: 15.,
+                color: palette::named::PURPLE.into(),
+                ..Default::default()
+            },
+            Circle {
+                position: [300., 300.],
+                radius: 50.,
+                color: palette::named::SADDLEBROWN.into(),
+                ..Default::default()
+            },
+        ])),
+    ])
+}
+
+fn main() {
+    let opts = Opts::parse();
+
+    let image = render_to_image(&create(), opts.width, opts.height, Camera::default()).unwrap();
+    image.save_png(&opts.output).unwrap();
+}
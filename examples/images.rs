@@ -0,0 +1,86 @@
+use vecdraw::{
+    run_event_loop, GridLayer, GroupLayer, Image, ImagesLayer, Line, LinesLayer, Sprite,
+};
+
+/// A gradient with a transparent hole in the middle.
+fn gradient(size: u32) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let (u, v) = (x as f32 / size as f32, y as f32 / size as f32);
+            let hole = (u - 0.5).powi(2) + (v - 0.5).powi(2) < 0.04;
+            data.extend_from_slice(&[
+                (u * 255.) as u8,
+                (v * 255.) as u8,
+                160,
+                if hole { 0 } else { 255 },
+            ]);
+        }
+    }
+    Image {
+        width: size,
+        height: size,
+        data,
+    }
+}
+
+fn checkerboard(size: u32, cells: u32) -> Image {
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let light = (x * cells / size + y * cells / size) % 2 == 1;
+            let value = if light { 220 } else { 40 };
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    Image {
+        width: size,
+        height: size,
+        data,
+    }
+}
+
+fn create() -> GroupLayer {
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(
+            ImagesLayer::new(
+                vec![gradient(256), checkerboard(64, 8)],
+                vec![
+                    Sprite {
+                        position: [-250., 250.],
+                        size: [400., 400.],
+                        image: 0,
+                        ..Default::default()
+                    },
+                    Sprite {
+                        position: [250., -250.],
+                        size: [300., 200.],
+                        rotation: std::f32::consts::FRAC_PI_6,
+                        image: 1,
+                        ..Default::default()
+                    },
+                    Sprite {
+                        position: [0., 0.],
+                        size: [300., 300.],
+                        opacity: 0.5,
+                        image: 0,
+                        ..Default::default()
+                    },
+                ],
+            )
+            .unwrap(),
+        ),
+        // Vector overlays draw on top of the images.
+        Box::new(LinesLayer::new(vec![Line {
+            start: [-450., -450.],
+            end: [450., 450.],
+            width: 4.,
+            color: palette::named::TOMATO.into(),
+        }])),
+    ])
+}
+
+fn main() {
+    run_event_loop(create());
+}
@@ -28,7 +28,6 @@ impl Layer for ManyLines {
     fn init_drawable(&self, draw_context: &DrawContext) -> Self::D {
         let mut rand = rand::thread_rng();
         let lines: Vec<Line> = (0..self.0)
-            .into_iter()
             .map(|_| {
                 let start = [
                     rand.gen_range(-EXTENT..EXTENT),
@@ -0,0 +1,36 @@
+use vecdraw::{run_event_loop, GridLayer, GroupLayer, Marker, MarkersLayer, Symbol};
+
+const SYMBOLS: [Symbol; 7] = [
+    Symbol::Circle,
+    Symbol::Square,
+    Symbol::Triangle,
+    Symbol::Diamond,
+    Symbol::Cross,
+    Symbol::Plus,
+    Symbol::Star,
+];
+
+fn create() -> GroupLayer {
+    let mut markers = Vec::new();
+    for (i, &symbol) in SYMBOLS.iter().enumerate() {
+        for j in 0..10 {
+            let x = -450. + 150. * i as f32;
+            let y = -450. + 100. * j as f32;
+            markers.push(Marker {
+                position: [x, y + 40. * (x / 200. + j as f32).sin()],
+                color: palette::named::STEELBLUE.into(),
+                size: 8. + 2. * j as f32,
+                symbol,
+            });
+        }
+    }
+
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(MarkersLayer::new(markers)),
+    ])
+}
+
+fn main() {
+    run_event_loop(create());
+}
@@ -0,0 +1,39 @@
+use palette::Srgb;
+use std::f32::consts::TAU;
+use vecdraw::{run_event_loop, Color, GridLayer, GroupLayer, MeshLayer, MeshVertex};
+
+const SIDES: u32 = 48;
+
+/// A color that cycles smoothly through the hues as `t` goes from 0 to 1.
+fn hue(t: f32) -> Color {
+    let channel = |offset: f32| ((0.5 + 0.5 * (TAU * (t - offset)).cos()) * 255.) as u8;
+    Srgb::new(channel(0.), channel(1. / 3.), channel(2. / 3.)).into()
+}
+
+/// A disk made of a fan of triangles around its center, with hues that
+/// change smoothly around its edge.
+fn color_wheel() -> MeshLayer {
+    let mut vertices = vec![MeshVertex {
+        position: [0., 0.],
+        color: Color::WHITE,
+    }];
+    let mut indices = Vec::new();
+
+    for i in 0..SIDES {
+        let t = i as f32 / SIDES as f32;
+        vertices.push(MeshVertex {
+            position: [400. * (TAU * t).cos(), 400. * (TAU * t).sin()],
+            color: hue(t),
+        });
+        indices.extend_from_slice(&[0, i + 1, (i + 1) % SIDES + 1]);
+    }
+
+    MeshLayer::new(vertices, indices).unwrap()
+}
+
+fn main() {
+    run_event_loop(GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(color_wheel()),
+    ]));
+}
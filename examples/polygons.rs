@@ -0,0 +1,57 @@
+use vecdraw::{run_event_loop, FillRule, GridLayer, GroupLayer, Polygon, PolygonsLayer};
+
+/// A five-pointed star drawn as a single self-intersecting ring.
+fn star(center: [f32; 2], radius: f32) -> Vec<[f32; 2]> {
+    (0..5)
+        .map(|i| {
+            let theta = std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * (i * 2) as f32 / 5.;
+            [
+                center[0] + radius * theta.cos(),
+                center[1] + radius * theta.sin(),
+            ]
+        })
+        .collect()
+}
+
+fn square(center: [f32; 2], half: f32, clockwise: bool) -> Vec<[f32; 2]> {
+    let mut points = vec![
+        [center[0] - half, center[1] - half],
+        [center[0] + half, center[1] - half],
+        [center[0] + half, center[1] + half],
+        [center[0] - half, center[1] + half],
+    ];
+    if clockwise {
+        points.reverse();
+    }
+    points
+}
+
+fn create() -> GroupLayer {
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(PolygonsLayer::new(vec![
+            Polygon {
+                rings: vec![star([-300., 250.], 200.)],
+                color: palette::named::GOLDENROD.into(),
+                fill_rule: FillRule::NonZero,
+            },
+            Polygon {
+                rings: vec![star([300., 250.], 200.)],
+                color: palette::named::GOLDENROD.into(),
+                fill_rule: FillRule::EvenOdd,
+            },
+            Polygon {
+                rings: vec![
+                    square([0., -300.], 200., false),
+                    square([0., -300.], 100., true),
+                ],
+                color: palette::named::SEAGREEN.into(),
+                fill_rule: FillRule::NonZero,
+            },
+        ])),
+    ])
+}
+
+fn main() {
+    run_event_loop(create());
+}
@@ -0,0 +1,47 @@
+use vecdraw::{
+    run_event_loop, Color, DashPattern, DashUnits, GridLayer, GroupLayer, LineJoin, Polyline,
+    PolylinesLayer,
+};
+
+fn zigzag(x: f32, join: LineJoin, color: Color) -> Polyline {
+    Polyline {
+        points: vec![
+            [x - 80., -400.],
+            [x + 80., -200.],
+            [x - 80., 0.],
+            [x + 80., 200.],
+            [x - 80., 400.],
+        ],
+        color,
+        width: 20.,
+        join,
+    }
+}
+
+fn create() -> GroupLayer {
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(PolylinesLayer::new(vec![
+            zigzag(
+                -400.,
+                LineJoin::Miter { limit: 4. },
+                palette::named::STEELBLUE.into(),
+            ),
+            zigzag(0., LineJoin::Round, palette::named::SALMON.into()),
+            zigzag(400., LineJoin::Bevel, palette::named::PURPLE.into()),
+        ])),
+        Box::new(
+            PolylinesLayer::new(vec![Polyline {
+                points: vec![[-550., -500.], [550., -500.], [550., 500.], [-550., 500.]],
+                color: palette::named::DIMGRAY.into(),
+                width: 2.,
+                join: LineJoin::default(),
+            }])
+            .with_dash(DashPattern::new(&[12., 6., 2., 6.], DashUnits::Pixels)),
+        ),
+    ])
+}
+
+fn main() {
+    run_event_loop(create());
+}
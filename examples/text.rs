@@ -0,0 +1,40 @@
+use clap::Parser;
+use vecdraw::{run_event_loop, Anchor, Font, GridLayer, GroupLayer, Text, TextLayer};
+
+#[derive(Parser)]
+struct Opts {
+    /// Path to a TrueType or OpenType font file.
+    font: String,
+}
+
+fn create(font: Font) -> GroupLayer {
+    let anchors = [
+        (Anchor::TopLeft, [-400., 400.]),
+        (Anchor::Center, [0., 0.]),
+        (Anchor::BottomRight, [400., -400.]),
+    ];
+
+    GroupLayer::new(vec![
+        Box::new(GridLayer::new(5, 5, palette::named::PALETURQUOISE.into())),
+        Box::new(TextLayer::new(
+            font,
+            anchors
+                .iter()
+                .map(|&(anchor, position)| Text {
+                    text: format!("{:?}\nvecdraw", anchor),
+                    position,
+                    size: 60.,
+                    color: palette::named::DARKSLATEGRAY.into(),
+                    anchor,
+                })
+                .collect(),
+        )),
+    ])
+}
+
+fn main() {
+    let opts = Opts::parse();
+    let font = Font::from_file(&opts.font).unwrap();
+
+    run_event_loop(create(font));
+}
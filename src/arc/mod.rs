@@ -0,0 +1,215 @@
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+
+use crate::color::Color;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::layer::{DrawContext, DrawState, Drawable, Layer};
+use crate::units::WidthUnits;
+use crate::GenericDrawable;
+use crate::GenericLayer;
+use std::borrow::Cow;
+use std::f32::consts::TAU;
+use winit::dpi::PhysicalSize;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct Arc {
+    pub center: [f32; 2],
+    pub color: Color,
+    pub radius: f32,
+    /// Angles in radians, counter-clockwise from the positive X axis. The arc
+    /// runs counter-clockwise from `start_angle` to `end_angle`, or clockwise
+    /// if `end_angle` is smaller, and is a full circle if they are a turn or
+    /// more apart.
+    pub start_angle: f32,
+    pub end_angle: f32,
+    /// When zero, the arc is a sector (pie slice) filled with `color`.
+    /// Otherwise it is stroked with butt ends, the stroke extending
+    /// `stroke_width` to each side of `radius` like `Line::width`.
+    pub stroke_width: f32,
+}
+
+impl Arc {
+    /// Returns the start and end angles, with the end no more than a turn
+    /// from the start.
+    pub(crate) fn angles(&self) -> (f32, f32) {
+        let sweep = (self.end_angle - self.start_angle).clamp(-TAU, TAU);
+        (self.start_angle, self.start_angle + sweep)
+    }
+
+    /// Returns the point on the arc's circle at `angle`.
+    pub(crate) fn point(&self, angle: f32) -> [f32; 2] {
+        let (sin, cos) = angle.sin_cos();
+        [
+            self.center[0] + self.radius * cos,
+            self.center[1] + self.radius * sin,
+        ]
+    }
+}
+
+impl GpuSerializable for Arc {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Arc>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ],
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArcsLayer {
+    data: Vec<Arc>,
+    units: WidthUnits,
+}
+
+impl ArcsLayer {
+    pub fn new(data: Vec<Arc>) -> Self {
+        ArcsLayer {
+            data,
+            units: WidthUnits::default(),
+        }
+    }
+
+    /// Sets the units of the stroke widths of every arc in the layer.
+    pub fn with_width_units(mut self, units: WidthUnits) -> Self {
+        self.units = units;
+        self
+    }
+}
+
+pub struct ArcsLayerDrawable {
+    render_pipeline: RenderPipeline,
+    pub instance_buffer: GpuBuffer<Arc>,
+}
+
+impl Drawable for ArcsLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.all());
+        render_pass.draw(0..6, 0..self.instance_buffer.len());
+    }
+}
+
+impl GenericLayer for ArcsLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+impl Layer for ArcsLayer {
+    type D = ArcsLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> ArcsLayerDrawable {
+        let DrawContext {
+            device,
+            sc_desc,
+            transform_layout,
+            sample_count,
+            ..
+        } = *draw_context;
+        let instance_buffer = GpuBuffer::new(&self.data, device);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Owned(
+                self.units.shader_source(include_str!("shader.wgsl")),
+            )),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[Arc::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                //cull_mode: Some(wgpu::Face::Back),
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        ArcsLayerDrawable {
+            render_pipeline,
+            instance_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.arcs(&self.data, self.units);
+    }
+}
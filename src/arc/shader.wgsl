@@ -0,0 +1,80 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    // Offset from the center of the arc in scene units, rotated so that the
+    // middle of the arc lies along the positive X axis.
+    [[location(1)]] coord: vec2<f32>;
+    // (radius, stroke width, half the angle the arc spans) of the arc.
+    [[location(2)]] shape: vec3<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+let PI: f32 = 3.14159265358979;
+
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(-1., -1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., 1.),
+);
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] center: vec2<f32>,
+    [[location(1)]] color: vec4<f32>,
+    [[location(2)]] radius: f32,
+    [[location(3)]] angles: vec2<f32>,
+    [[location(4)]] width: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let stroke_width: f32 = width * width_scale();
+    let outer: f32 = radius + max(stroke_width, 0.);
+    let local: vec2<f32> = corners[in_vertex_index] * outer;
+
+    let middle: f32 = (angles.x + angles.y) / 2.;
+    let c: f32 = cos(middle);
+    let s: f32 = sin(middle);
+
+    out.color = color;
+    out.coord = vec2<f32>(c * local.x + s * local.y, c * local.y - s * local.x);
+    out.shape = vec3<f32>(radius, stroke_width, min(abs(angles.y - angles.x) / 2., PI));
+    out.position = uniforms.transform * vec4<f32>(center + local, 0., 1.);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let radius: f32 = in.shape.x;
+    let stroke_width: f32 = in.shape.y;
+    let half_angle: f32 = in.shape.z;
+
+    // The arc is symmetric about the X axis.
+    let p: vec2<f32> = vec2<f32>(in.coord.x, abs(in.coord.y));
+    let r: f32 = length(p);
+
+    // Signed distance to the ray bounding the arc's angle, negative on the
+    // side of the arc. A full circle has no such bound.
+    var angular: f32 = -1.0e30;
+    if (half_angle < PI) {
+        let edge: vec2<f32> = vec2<f32>(cos(half_angle), sin(half_angle));
+        let m: f32 = length(p - edge * max(dot(p, edge), 0.));
+        angular = m * sign(edge.x * p.y - edge.y * p.x);
+    }
+
+    var radial: f32 = r - radius;
+    if (stroke_width > 0.) {
+        radial = abs(r - radius) - stroke_width;
+    }
+
+    let d: f32 = max(radial, angular);
+    let alpha: f32 = clamp(0.5 - d / fwidth(d), 0., 1.);
+
+    if (alpha < 0.01) {
+        discard;
+    }
+
+    return in.color * alpha;
+}
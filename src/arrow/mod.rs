@@ -0,0 +1,387 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::line::Line;
+use crate::units::WidthUnits;
+use std::borrow::Cow;
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+/// How far the back of a `Barbed` head is swept forward in its middle,
+/// relative to its length. Must match `shader.wgsl`.
+const BARB_DEPTH: f32 = 0.3;
+
+/// The shape of an arrow's heads.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ArrowHead {
+    /// A filled triangle.
+    #[default]
+    Triangle,
+    /// Two strokes as wide as the line, meeting at the tip.
+    Open,
+    /// A filled triangle with a notch in its back.
+    Barbed,
+}
+
+impl ArrowHead {
+    fn fragment_entry_point(self) -> &'static str {
+        match self {
+            ArrowHead::Triangle => "fs_triangle",
+            ArrowHead::Open => "fs_open",
+            ArrowHead::Barbed => "fs_barbed",
+        }
+    }
+
+    /// Distance from the tip of a filled head to the middle of its back,
+    /// relative to its length.
+    fn notch(self) -> f32 {
+        match self {
+            ArrowHead::Barbed => 1. - BARB_DEPTH,
+            _ => 1.,
+        }
+    }
+}
+
+/// Which ends of an arrow have heads.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum ArrowEnds {
+    Start,
+    #[default]
+    End,
+    Both,
+}
+
+/// The heads drawn on every arrow in an `ArrowsLayer`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ArrowStyle {
+    pub head: ArrowHead,
+    pub ends: ArrowEnds,
+    /// Distance from the tip of a head back to its corners, along the line.
+    pub head_length: f32,
+    /// Like `Line::width`, each head extends `head_width` to each side of
+    /// the line.
+    pub head_width: f32,
+}
+
+/// A triangle at the end of each arrow, sized for lines of `width` 1: five
+/// times as wide as the line and twelve units long. Head sizes don't follow
+/// the line width, so wider lines need larger heads.
+impl Default for ArrowStyle {
+    fn default() -> Self {
+        ArrowStyle {
+            head: ArrowHead::Triangle,
+            ends: ArrowEnds::End,
+            head_length: 12.,
+            head_width: 5.,
+        }
+    }
+}
+
+impl ArrowStyle {
+    /// The (length, width) of the heads at the start and end of an arrow,
+    /// or zero where there is no head, scaled by `scale`.
+    fn heads(&self, scale: f32) -> [[f32; 2]; 2] {
+        let head = [self.head_length * scale, self.head_width * scale];
+        match self.ends {
+            ArrowEnds::Start => [head, [0., 0.]],
+            ArrowEnds::End => [[0., 0.], head],
+            ArrowEnds::Both => [head, head],
+        }
+    }
+
+    /// The outline of an arrow from `start` to `end` with `Triangle` or
+    /// `Barbed` heads. Sizes are scaled by `scale`, and must be in the same
+    /// units as the points once scaled.
+    pub(crate) fn outline(
+        &self,
+        start: [f32; 2],
+        end: [f32; 2],
+        width: f32,
+        scale: f32,
+    ) -> Vec<[f32; 2]> {
+        let (length, point) = frame(start, end);
+        let width = width * scale;
+        let notch = self.head.notch();
+
+        // One side of the outline, from the tip at the start to the tip at
+        // the end; the other side mirrors it.
+        let [start_head, end_head] = self.heads(scale);
+        let mut side = head_outline(start_head, width, notch);
+        side.reverse();
+        side.extend(
+            head_outline(end_head, width, notch)
+                .iter()
+                .map(|&[x, y]| [length - x, y]),
+        );
+
+        let mut outline: Vec<[f32; 2]> = side.iter().map(|&[x, y]| point(x, y)).collect();
+        outline.extend(side.iter().rev().map(|&[x, y]| point(x, -y)));
+        outline
+    }
+
+    /// The polylines through the corners and tip of each of the `Open`
+    /// heads of an arrow from `start` to `end`, with sizes scaled by
+    /// `scale`.
+    pub(crate) fn barbs(&self, start: [f32; 2], end: [f32; 2], scale: f32) -> Vec<[[f32; 2]; 3]> {
+        let (length, point) = frame(start, end);
+        let [start_head, end_head] = self.heads(scale);
+
+        let mut barbs = Vec::new();
+        if start_head[0] > 0. {
+            let [l, w] = start_head;
+            barbs.push([point(l, w), point(0., 0.), point(l, -w)]);
+        }
+        if end_head[0] > 0. {
+            let [l, w] = end_head;
+            barbs.push([
+                point(length - l, w),
+                point(length, 0.),
+                point(length - l, -w),
+            ]);
+        }
+        barbs
+    }
+}
+
+/// The length of the line from `start` to `end`, and a function mapping
+/// (along, across) coordinates relative to it to points.
+fn frame(start: [f32; 2], end: [f32; 2]) -> (f32, impl Fn(f32, f32) -> [f32; 2]) {
+    let delta = [end[0] - start[0], end[1] - start[1]];
+    let length = delta[0].hypot(delta[1]);
+    let dir = if length > 0. {
+        [delta[0] / length, delta[1] / length]
+    } else {
+        [1., 0.]
+    };
+
+    let point = move |along: f32, across: f32| {
+        [
+            start[0] + dir[0] * along - dir[1] * across,
+            start[1] + dir[1] * along + dir[0] * across,
+        ]
+    };
+    (length, point)
+}
+
+/// Where one side of a filled head meets the shaft, its corner and its tip,
+/// as (distance back from the tip, distance from the center line). A head of
+/// zero length is a butt end.
+fn head_outline([length, head_width]: [f32; 2], width: f32, notch: f32) -> Vec<[f32; 2]> {
+    if length <= 0. {
+        return vec![[0., width], [0., 0.]];
+    }
+
+    // The shaft meets the back of the head, which runs from the notch on the
+    // center line out to the corner.
+    let notch = notch * length;
+    let shaft = notch + (length - notch) * (width / head_width).min(1.);
+    vec![[shaft, width], [length, head_width], [0., 0.]]
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+struct ArrowInstance {
+    start: [f32; 2],
+    end: [f32; 2],
+    color: Color,
+    width: f32,
+    heads: [[f32; 2]; 2],
+}
+
+impl GpuSerializable for ArrowInstance {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<ArrowInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+            ],
+        }
+    }
+}
+
+fn instances(data: &[Line], style: &ArrowStyle) -> Vec<ArrowInstance> {
+    let heads = style.heads(1.);
+    data.iter()
+        .map(|line| ArrowInstance {
+            start: line.start,
+            end: line.end,
+            color: line.color,
+            width: line.width,
+            heads,
+        })
+        .collect()
+}
+
+/// Draws each `Line` as an arrow from its `start` to its `end`.
+pub struct ArrowsLayer {
+    data: Vec<Line>,
+    style: ArrowStyle,
+    units: WidthUnits,
+}
+
+impl ArrowsLayer {
+    pub fn new(data: Vec<Line>, style: ArrowStyle) -> Self {
+        ArrowsLayer {
+            data,
+            style,
+            units: WidthUnits::default(),
+        }
+    }
+
+    /// Sets the units of the widths and head sizes of every arrow in the
+    /// layer.
+    pub fn with_width_units(mut self, units: WidthUnits) -> Self {
+        self.units = units;
+        self
+    }
+}
+
+pub struct ArrowsLayerDrawable {
+    render_pipeline: RenderPipeline,
+    style: ArrowStyle,
+    instance_buffer: GpuBuffer<ArrowInstance>,
+}
+
+impl ArrowsLayerDrawable {
+    /// Replaces the arrows drawn by this drawable, keeping the layer's style.
+    pub fn set_arrows(&mut self, data: &[Line], update_state: &UpdateState) {
+        self.instance_buffer.update(
+            &instances(data, &self.style),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+    }
+}
+
+impl Drawable for ArrowsLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.all());
+        render_pass.draw(0..6, 0..self.instance_buffer.len());
+    }
+}
+
+impl Layer for ArrowsLayer {
+    type D = ArrowsLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> ArrowsLayerDrawable {
+        let DrawContext {
+            device,
+            sc_desc,
+            transform_layout,
+            sample_count,
+            ..
+        } = *draw_context;
+        let instance_buffer = GpuBuffer::new(&instances(&self.data, &self.style), device);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Owned(
+                self.units.shader_source(include_str!("shader.wgsl")),
+            )),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[ArrowInstance::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: self.style.head.fragment_entry_point(),
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        ArrowsLayerDrawable {
+            render_pipeline,
+            style: self.style,
+            instance_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.arrows(&self.data, &self.style, self.units);
+    }
+}
+
+impl GenericLayer for ArrowsLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
@@ -0,0 +1,188 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    // Position relative to the arrow, as (distance along it from `start`,
+    // signed distance from its center line).
+    [[location(1)]] local: vec2<f32>;
+    // (length, width) of the arrow's line.
+    [[location(2)]] extent: vec2<f32>;
+    // (length, width) of the heads at the start and end of the arrow, zero
+    // where there is none.
+    [[location(3)]] heads: vec4<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+// This must match `BARB_DEPTH` in mod.rs.
+let BARB_DEPTH: f32 = 0.3;
+
+// (along, across) for each corner, where along is 0 at the back of the quad
+// and 1 at the front.
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(0., -1.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(1., 1.),
+);
+
+fn vertex(in_vertex_index: u32, start: vec2<f32>, end: vec2<f32>, color: vec4<f32>,
+    width: f32, heads: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = color;
+
+    let len: f32 = length(end - start);
+    var dir: vec2<f32> = vec2<f32>(1., 0.);
+    if (len > 0.) {
+        dir = (end - start) / len;
+    }
+    let perp: vec2<f32> = vec2<f32>(-dir.y, dir.x);
+
+    // Open heads are stroked `width` past their corners and tips, and heads
+    // longer than the line reach back past its other end.
+    let pad: f32 = max(max(heads.y, heads.w), width) + width;
+    let back: f32 = min(0., len - heads.z) - pad;
+    let front: f32 = max(len, heads.x) + pad;
+
+    let corner: vec2<f32> = corners[in_vertex_index];
+    let along: f32 = back + corner.x * (front - back);
+    let across: f32 = corner.y * pad;
+
+    out.local = vec2<f32>(along, across);
+    out.extent = vec2<f32>(len, width);
+    out.heads = heads;
+    out.position = uniforms.transform * vec4<f32>(start + dir * along + perp * across, 0., 1.);
+
+    return out;
+}
+
+[[stage(vertex)]]
+fn vs_main(
+    [[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] start: vec2<f32>,
+    [[location(1)]] end: vec2<f32>,
+    [[location(2)]] color: vec4<f32>,
+    [[location(3)]] width: f32,
+    [[location(4)]] heads: vec4<f32>,
+) -> VertexOutput {
+    let scale: f32 = width_scale();
+    return vertex(in_vertex_index, start, end, color, width * scale, heads * scale);
+}
+
+// Turns a signed distance from the edge of the arrow (negative inside) into
+// a color, fading out over one pixel.
+fn shade(color: vec4<f32>, d: f32) -> vec4<f32> {
+    let alpha: f32 = clamp(0.5 - d / fwidth(d), 0., 1.);
+
+    return color * alpha;
+}
+
+fn segment_distance(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>) -> f32 {
+    let e: vec2<f32> = b - a;
+    let t: f32 = clamp(dot(p - a, e) / max(dot(e, e), 1.0e-12), 0., 1.);
+
+    return length(p - a - e * t);
+}
+
+// Adds the edge from `a` to `b` of a polygon to `acc`, which holds the
+// squared distance to the nearest edge so far and -1 or 1 for whether an odd
+// or even number of edges cross the ray from `p` along the positive X axis.
+fn polygon_edge(p: vec2<f32>, a: vec2<f32>, b: vec2<f32>, acc: vec2<f32>) -> vec2<f32> {
+    let d: f32 = segment_distance(p, a, b);
+
+    let e: vec2<f32> = b - a;
+    let w: vec2<f32> = p - a;
+    let c1: bool = p.y >= a.y;
+    let c2: bool = p.y < b.y;
+    let c3: bool = e.x * w.y > e.y * w.x;
+
+    var s: f32 = acc.y;
+    if ((c1 && c2 && c3) || (!c1 && !c2 && !c3)) {
+        s = -s;
+    }
+
+    return vec2<f32>(min(acc.x, d * d), s);
+}
+
+struct HeadOutline {
+    shaft: vec2<f32>;
+    corner: vec2<f32>;
+    tip: vec2<f32>;
+};
+
+// Where the upper side of a filled head meets the shaft, its corner and its
+// tip, for a head with its tip at `tip` pointing away from `back`, the
+// direction of the shaft. A head of zero length is a butt end.
+fn head_outline(tip: f32, back: f32, size: vec2<f32>, width: f32, notch: f32) -> HeadOutline {
+    var out: HeadOutline;
+    out.tip = vec2<f32>(tip, 0.);
+
+    if (size.x <= 0.) {
+        out.shaft = vec2<f32>(tip, width);
+        out.corner = out.shaft;
+        return out;
+    }
+
+    // The shaft meets the back of the head, which runs from the notch on the
+    // center line out to the corner.
+    let n: f32 = notch * size.x;
+    let shaft: f32 = n + (size.x - n) * min(width / size.y, 1.);
+    out.shaft = vec2<f32>(tip + back * shaft, width);
+    out.corner = vec2<f32>(tip + back * size.x, size.y);
+    return out;
+}
+
+// Signed distance to an arrow with filled heads whose backs are notched
+// `notch` of the way from their corners to their tips, negative inside.
+fn filled_distance(in: VertexOutput, notch: f32) -> f32 {
+    // The arrow is symmetric about its center line, so the distance to its
+    // upper side is the distance to the whole outline, and the center line
+    // closes that side into a polygon without crossing the ray from any
+    // point above it.
+    let p: vec2<f32> = vec2<f32>(in.local.x, abs(in.local.y));
+    let len: f32 = in.extent.x;
+    let width: f32 = in.extent.y;
+
+    let start: HeadOutline = head_outline(0., 1., in.heads.xy, width, notch);
+    let end: HeadOutline = head_outline(len, -1., in.heads.zw, width, notch);
+
+    var acc: vec2<f32> = vec2<f32>(3.40282347e38, 1.);
+    acc = polygon_edge(p, start.tip, start.corner, acc);
+    acc = polygon_edge(p, start.corner, start.shaft, acc);
+    acc = polygon_edge(p, start.shaft, end.shaft, acc);
+    acc = polygon_edge(p, end.shaft, end.corner, acc);
+    acc = polygon_edge(p, end.corner, end.tip, acc);
+
+    return acc.y * sqrt(acc.x);
+}
+
+[[stage(fragment)]]
+fn fs_triangle(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return shade(in.color, filled_distance(in, 1.));
+}
+
+[[stage(fragment)]]
+fn fs_barbed(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    return shade(in.color, filled_distance(in, 1. - BARB_DEPTH));
+}
+
+[[stage(fragment)]]
+fn fs_open(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let p: vec2<f32> = vec2<f32>(in.local.x, abs(in.local.y));
+    let len: f32 = in.extent.x;
+    let width: f32 = in.extent.y;
+
+    // A butt-ended shaft, and round-ended strokes from each tip back to the
+    // corners of its head, which round off the join at the tip.
+    var d: f32 = max(abs(p.x - len / 2.) - len / 2., p.y - width);
+    if (in.heads.x > 0.) {
+        let corner: vec2<f32> = vec2<f32>(in.heads.x, in.heads.y);
+        d = min(d, segment_distance(p, vec2<f32>(0., 0.), corner) - width);
+    }
+    if (in.heads.z > 0.) {
+        let corner: vec2<f32> = vec2<f32>(len - in.heads.z, in.heads.w);
+        d = min(d, segment_distance(p, vec2<f32>(len, 0.), corner) - width);
+    }
+
+    return shade(in.color, d);
+}
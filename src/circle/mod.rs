@@ -4,18 +4,25 @@ use wgpu::{
 };
 
 use crate::color::Color;
+use crate::export::ShapeVisitor;
 use crate::gpu_data::{GpuBuffer, GpuSerializable};
 use crate::layer::{DrawContext, DrawState, Drawable, Layer};
+use crate::units::WidthUnits;
 use crate::GenericDrawable;
 use crate::GenericLayer;
 use std::borrow::Cow;
+use winit::dpi::PhysicalSize;
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct Circle {
     pub position: [f32; 2],
     pub color: Color,
     pub radius: f32,
+    /// Like `Rectangle::stroke_width`, the stroke extends `stroke_width` to
+    /// each side of `radius`. The fill is only drawn inside the stroke.
+    pub stroke_width: f32,
+    pub stroke_color: Color,
 }
 
 impl GpuSerializable for Circle {
@@ -43,6 +50,16 @@ impl GpuSerializable for Circle {
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float32,
                 },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
             ],
         }
     }
@@ -51,11 +68,21 @@ impl GpuSerializable for Circle {
 #[derive(Debug)]
 pub struct CirclesLayer {
     data: Vec<Circle>,
+    units: WidthUnits,
 }
 
 impl CirclesLayer {
     pub fn new(data: Vec<Circle>) -> Self {
-        CirclesLayer { data }
+        CirclesLayer {
+            data,
+            units: WidthUnits::default(),
+        }
+    }
+
+    /// Sets the units of the stroke widths of every circle in the layer.
+    pub fn with_width_units(mut self, units: WidthUnits) -> Self {
+        self.units = units;
+        self
     }
 }
 
@@ -65,7 +92,7 @@ pub struct CirclesLayerDrawable {
 }
 
 impl Drawable for CirclesLayerDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         let mut render_pass = draw_state.render_pass.borrow_mut();
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, draw_state.bind_group, &[]);
@@ -78,6 +105,10 @@ impl GenericLayer for CirclesLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
         GenericDrawable::new(self.init_drawable(draw_context))
     }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
 }
 
 impl Layer for CirclesLayer {
@@ -88,6 +119,8 @@ impl Layer for CirclesLayer {
             device,
             sc_desc,
             transform_layout,
+            sample_count,
+            ..
         } = *draw_context;
         let instance_buffer = GpuBuffer::new(&self.data, device);
 
@@ -100,7 +133,9 @@ impl Layer for CirclesLayer {
 
         let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
             label: None,
-            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            source: ShaderSource::Wgsl(Cow::Owned(
+                self.units.shader_source(include_str!("shader.wgsl")),
+            )),
             flags: Default::default(),
         });
 
@@ -137,7 +172,7 @@ impl Layer for CirclesLayer {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -148,4 +183,8 @@ impl Layer for CirclesLayer {
             instance_buffer,
         }
     }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.circles(&self.data, self.units);
+    }
 }
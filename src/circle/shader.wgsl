@@ -1,17 +1,13 @@
 struct VertexOutput {
     [[location(0)]] color: vec4<f32>;
-    [[location(1)]] coord: vec2<f32>;
+    [[location(1)]] stroke_color: vec4<f32>;
+    // Offset from the center of the circle, in scene units.
+    [[location(2)]] coord: vec2<f32>;
+    // (radius, stroke width) of the circle.
+    [[location(3)]] shape: vec2<f32>;
     [[builtin(position)]] position: vec4<f32>;
 };
 
-[[block]]
-struct Uniforms {
-    transform: mat4x4<f32>;
-};
-
-[[group(0), binding(0)]]
-var uniforms: Uniforms;
-
 let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
     vec2<f32>(-1., -1.),
     vec2<f32>(1., -1.),
@@ -21,36 +17,59 @@ let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
     vec2<f32>(1., 1.),
 );
 
-[[stage(vertex)]]
-fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
-    [[location(0)]] position: vec4<f32>,
-    [[location(1)]] color: vec4<f32>,
-    [[location(2)]] radius: f32,
+fn vertex(in_vertex_index: u32, position: vec2<f32>, color: vec4<f32>, radius: f32,
+    stroke_width: f32, stroke_color: vec4<f32>,
 ) -> VertexOutput {
     var out: VertexOutput;
 
+    let outer: f32 = radius + max(stroke_width, 0.);
+
     out.color = color;
-    out.coord = corners[in_vertex_index];
-    out.position = uniforms.transform * vec4<f32>(
-        position.x + radius * out.coord.x,
-        position.y + radius * out.coord.y,
-        0.,
-        1.
-    );
+    out.stroke_color = stroke_color;
+    out.coord = corners[in_vertex_index] * outer;
+    out.shape = vec2<f32>(radius, stroke_width);
+    out.position = uniforms.transform * vec4<f32>(position + out.coord, 0., 1.);
 
     return out;
 }
 
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] color: vec4<f32>,
+    [[location(2)]] radius: f32,
+    [[location(3)]] stroke_width: f32,
+    [[location(4)]] stroke_color: vec4<f32>,
+) -> VertexOutput {
+    return vertex(in_vertex_index, position, color, radius, stroke_width * width_scale(),
+        stroke_color);
+}
+
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    let r: f32 = dot(in.coord, in.coord);
+    let r: f32 = length(in.coord);
     let delta: f32 = fwidth(r);
+    let radius: f32 = in.shape.x;
+    let stroke_width: f32 = in.shape.y;
 
-    let alpha: f32 = 1.0 - smoothStep(1.0 - delta*2., 1.0, r);
+    if (stroke_width <= 0.) {
+        let alpha: f32 = clamp(0.5 - (r - radius) / delta, 0., 1.);
+        if (alpha < 0.01) {
+            discard;
+        }
 
-    if (alpha < 0.01) {
+        return in.color * alpha;
+    }
+
+    // Coverage of everything inside the outer edge, and of the fill inside
+    // the inner edge. The stroke covers the difference, so the two edges are
+    // anti-aliased independently and the fill never shows through the stroke.
+    let outer: f32 = clamp(0.5 - (r - radius - stroke_width) / delta, 0., 1.);
+    let inner: f32 = clamp(0.5 - (r - radius + stroke_width) / delta, 0., 1.);
+
+    if (outer < 0.01) {
         discard;
     }
 
-    return in.color * alpha;
-}
\ No newline at end of file
+    return in.stroke_color * (outer - inner) + in.color * inner;
+}
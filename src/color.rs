@@ -1,7 +1,7 @@
 use palette::{Srgb, Srgba};
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct Color(pub u32);
 
 impl From<Srgb<u8>> for Color {
@@ -22,3 +22,24 @@ impl From<Srgba<u8>> for Color {
         ]))
     }
 }
+
+impl Color {
+    pub const WHITE: Color = Color(u32::from_ne_bytes([0xff, 0xff, 0xff, 0xff]));
+    pub const TRANSPARENT: Color = Color(0);
+
+    pub fn to_rgba(self) -> [u8; 4] {
+        bytemuck::cast(self.0)
+    }
+}
+
+impl From<Color> for wgpu::Color {
+    fn from(color: Color) -> Self {
+        let [r, g, b, a] = color.to_rgba();
+        wgpu::Color {
+            r: r as f64 / 255.,
+            g: g as f64 / 255.,
+            b: b as f64 / 255.,
+            a: a as f64 / 255.,
+        }
+    }
+}
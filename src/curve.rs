@@ -0,0 +1,269 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::dash::{DashBinding, DashPattern};
+use crate::export::ShapeVisitor;
+use crate::gpu_data::GpuBuffer;
+use crate::polyline::{stroke_pipeline, stroke_polyline, LineJoin, StrokeVertex};
+use cgmath::{InnerSpace, Vector2};
+use wgpu::RenderPipeline;
+use winit::dpi::PhysicalSize;
+
+/// Largest distance, in pixels, between a curve and the segments it is drawn
+/// with.
+const TOLERANCE_PIXELS: f32 = 0.25;
+
+/// Upper bound on the number of segments a single curve is flattened into.
+const MAX_SEGMENTS: u32 = 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Bezier {
+    /// Start point, control point and end point.
+    Quadratic([[f32; 2]; 3]),
+    /// Start point, two control points and end point.
+    Cubic([[f32; 2]; 4]),
+}
+
+impl Bezier {
+    fn point(&self, t: f32) -> Vector2<f32> {
+        let s = 1. - t;
+        match *self {
+            Bezier::Quadratic([p0, p1, p2]) => {
+                Vector2::from(p0) * (s * s)
+                    + Vector2::from(p1) * (2. * s * t)
+                    + Vector2::from(p2) * (t * t)
+            }
+            Bezier::Cubic([p0, p1, p2, p3]) => {
+                Vector2::from(p0) * (s * s * s)
+                    + Vector2::from(p1) * (3. * s * s * t)
+                    + Vector2::from(p2) * (3. * s * t * t)
+                    + Vector2::from(p3) * (t * t * t)
+            }
+        }
+    }
+
+    /// Number of equal parameter steps needed for the chords to stay within
+    /// `tolerance` of the curve. A chord spanning a step of `h` deviates from
+    /// the curve by at most `h² * max|B''| / 8`.
+    fn segments(&self, tolerance: f32) -> u32 {
+        let second_derivative = match *self {
+            Bezier::Quadratic([p0, p1, p2]) => {
+                2. * (Vector2::from(p0) - Vector2::from(p1) * 2. + Vector2::from(p2)).magnitude()
+            }
+            Bezier::Cubic([p0, p1, p2, p3]) => {
+                let (p0, p1, p2, p3) = (
+                    Vector2::from(p0),
+                    Vector2::from(p1),
+                    Vector2::from(p2),
+                    Vector2::from(p3),
+                );
+                6. * (p0 - p1 * 2. + p2)
+                    .magnitude()
+                    .max((p1 - p2 * 2. + p3).magnitude())
+            }
+        };
+
+        let segments = (second_derivative / (8. * tolerance)).sqrt().ceil();
+        if segments.is_finite() {
+            (segments as u32).clamp(1, MAX_SEGMENTS)
+        } else {
+            MAX_SEGMENTS
+        }
+    }
+
+    /// Approximates the curve with a polyline that stays within `tolerance`
+    /// of it.
+    pub fn flatten(&self, tolerance: f32) -> Vec<[f32; 2]> {
+        let segments = self.segments(tolerance);
+        (0..=segments)
+            .map(|i| self.point(i as f32 / segments as f32).into())
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Curve {
+    pub bezier: Bezier,
+    pub color: Color,
+    /// Like `Line::width`, the stroke extends `width` to each side of the
+    /// curve.
+    pub width: f32,
+}
+
+fn stroke_vertices(data: &[Curve], tolerance: f32) -> Vec<StrokeVertex> {
+    let mut vertices = Vec::new();
+    for curve in data {
+        stroke_polyline(
+            &curve.bezier.flatten(tolerance),
+            curve.width,
+            LineJoin::Round,
+            curve.color,
+            &mut vertices,
+        );
+    }
+    vertices
+}
+
+/// Like `PolylinesLayer`, curve widths are always in scene units.
+pub struct CurvesLayer {
+    data: Vec<Curve>,
+    dash: Option<DashPattern>,
+}
+
+impl CurvesLayer {
+    pub fn new(data: Vec<Curve>) -> Self {
+        CurvesLayer { data, dash: None }
+    }
+
+    /// Dashes every curve in the layer, measuring along the curve from its
+    /// start point.
+    pub fn with_dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+}
+
+/// Draws curves as polylines flattened for the current zoom level.
+///
+/// The curves are flattened again whenever the zoom changes enough to make
+/// the previous approximation visibly coarse, or wastefully fine.
+pub struct CurvesLayerDrawable {
+    render_pipeline: RenderPipeline,
+    dash: DashBinding,
+    data: Vec<Curve>,
+    vertex_buffer: GpuBuffer<StrokeVertex>,
+    /// Tolerance, in scene units, that the vertex buffer was flattened with.
+    tolerance: Option<f32>,
+}
+
+impl CurvesLayerDrawable {
+    /// Replaces the curves drawn by this drawable.
+    pub fn set_curves(&mut self, data: &[Curve], update_state: &UpdateState) {
+        self.data = data.to_vec();
+        self.tolerance = None;
+        self.update(update_state);
+    }
+}
+
+impl Drawable for CurvesLayerDrawable {
+    fn update(&mut self, update_state: &UpdateState) {
+        // Rounding the tolerance down to a power of two keeps it within a
+        // factor of two of the target while only flattening again when the
+        // zoom crosses one of those steps.
+        let tolerance = (TOLERANCE_PIXELS * update_state.pixel_size())
+            .log2()
+            .floor()
+            .exp2();
+        if !tolerance.is_normal() || self.tolerance == Some(tolerance) {
+            return;
+        }
+
+        self.vertex_buffer.update(
+            &stroke_vertices(&self.data, tolerance),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+        self.tolerance = Some(tolerance);
+    }
+
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_bind_group(1, &self.dash.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.all());
+        render_pass.draw(0..self.vertex_buffer.len(), 0..1);
+    }
+}
+
+impl Layer for CurvesLayer {
+    type D = CurvesLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> CurvesLayerDrawable {
+        // The zoom level isn't known until the first update, which flattens
+        // the curves.
+        let vertex_buffer = GpuBuffer::new(&[], draw_context.device);
+        let dash = DashBinding::new(draw_context.device, self.dash.as_ref());
+
+        CurvesLayerDrawable {
+            render_pipeline: stroke_pipeline(draw_context, &dash),
+            dash,
+            data: self.data.clone(),
+            vertex_buffer,
+            tolerance: None,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.curves(&self.data, self.dash.as_ref());
+    }
+}
+
+impl GenericLayer for CurvesLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quadratic(height: f32) -> Bezier {
+        Bezier::Quadratic([[0., 0.], [50., height], [100., 0.]])
+    }
+
+    #[test]
+    fn segments_grow_with_curvature() {
+        let counts: Vec<usize> = [0., 10., 100., 1000.]
+            .iter()
+            .map(|&height| quadratic(height).flatten(0.25).len())
+            .collect();
+
+        // A straight curve needs a single segment.
+        assert_eq!(counts[0], 2);
+        for pair in counts.windows(2) {
+            assert!(pair[0] < pair[1], "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn segments_drop_with_tolerance() {
+        let curve = Bezier::Cubic([[0., 0.], [0., 100.], [100., -100.], [100., 0.]]);
+        let counts: Vec<usize> = [0.01, 0.1, 1., 10.]
+            .iter()
+            .map(|&tolerance| curve.flatten(tolerance).len())
+            .collect();
+
+        for pair in counts.windows(2) {
+            assert!(pair[0] > pair[1], "{:?}", counts);
+        }
+    }
+
+    #[test]
+    fn segments_capped() {
+        let points = quadratic(1e9).flatten(1e-6);
+        assert_eq!(points.len(), MAX_SEGMENTS as usize + 1);
+
+        let points = quadratic(100.).flatten(0.);
+        assert_eq!(points.len(), MAX_SEGMENTS as usize + 1);
+    }
+
+    #[test]
+    fn endpoints_exact() {
+        let curves = [
+            Bezier::Quadratic([[0.1, 0.2], [-30., 7.], [12.345, -6.789]]),
+            Bezier::Cubic([[0.1, 0.2], [5., 90.], [-40., 3.], [12.345, -6.789]]),
+        ];
+        for curve in &curves {
+            let points = curve.flatten(0.01);
+            assert_eq!(points[0], [0.1, 0.2]);
+            assert_eq!(*points.last().unwrap(), [12.345, -6.789]);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsage, Device, ShaderStage,
+};
+
+/// The most dash and gap lengths a pattern can have, after odd-length
+/// patterns are repeated to make them even.
+pub const MAX_DASHES: usize = 8;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DashUnits {
+    /// Lengths scale with the scene when zooming.
+    Scene,
+    /// Lengths stay the same number of screen pixels at every zoom level.
+    Pixels,
+}
+
+/// Alternating dash and gap lengths along a stroke.
+///
+/// Like SVG's `stroke-dasharray`, a pattern with an odd number of lengths is
+/// repeated once so that dashes and gaps alternate. Zero-length dashes with
+/// round caps draw dots.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DashPattern {
+    dashes: Vec<f32>,
+    offset: f32,
+    units: DashUnits,
+}
+
+impl DashPattern {
+    /// Panics if `dashes` is empty, has more than `MAX_DASHES` lengths once
+    /// made even, contains a negative length, or sums to zero.
+    pub fn new(dashes: &[f32], units: DashUnits) -> Self {
+        let dashes = if dashes.len() % 2 == 1 {
+            dashes.repeat(2)
+        } else {
+            dashes.to_vec()
+        };
+
+        assert!(
+            !dashes.is_empty() && dashes.len() <= MAX_DASHES,
+            "Dash patterns must have between 1 and {} lengths",
+            MAX_DASHES
+        );
+        assert!(
+            dashes.iter().all(|&d| d >= 0.) && dashes.iter().sum::<f32>() > 0.,
+            "Dash lengths must be non-negative and not all zero"
+        );
+
+        DashPattern {
+            dashes,
+            offset: 0.,
+            units,
+        }
+    }
+
+    /// Starts the pattern `offset` into its first dash, like SVG's
+    /// `stroke-dashoffset`.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn dashes(&self) -> &[f32] {
+        &self.dashes
+    }
+
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn units(&self) -> DashUnits {
+        self.units
+    }
+
+    fn period(&self) -> f32 {
+        self.dashes.iter().sum()
+    }
+}
+
+/// The `Dash` uniform block declared in dash.wgsl, holding the
+/// (start, end) of each dash within one period of the pattern. A `count` of
+/// zero draws solid strokes.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
+struct DashUniform {
+    intervals: [f32; MAX_DASHES],
+    count: u32,
+    pixels: u32,
+    offset: f32,
+    period: f32,
+}
+
+impl From<Option<&DashPattern>> for DashUniform {
+    fn from(pattern: Option<&DashPattern>) -> Self {
+        let pattern = match pattern {
+            Some(pattern) => pattern,
+            None => return DashUniform::default(),
+        };
+
+        let mut intervals = [0.; MAX_DASHES];
+        let mut start = 0.;
+        for (interval, pair) in intervals.chunks_mut(2).zip(pattern.dashes.chunks(2)) {
+            interval[0] = start;
+            interval[1] = start + pair[0];
+            start += pair[0] + pair[1];
+        }
+
+        DashUniform {
+            intervals,
+            count: (pattern.dashes.len() / 2) as u32,
+            pixels: (pattern.units == DashUnits::Pixels) as u32,
+            offset: pattern.offset,
+            period: pattern.period(),
+        }
+    }
+}
+
+/// Bind group holding a layer's dash pattern, bound as group 1 by layers
+/// that support dashing.
+pub(crate) struct DashBinding {
+    _buffer: Buffer,
+    pub layout: BindGroupLayout,
+    pub bind_group: BindGroup,
+}
+
+impl DashBinding {
+    pub fn new(device: &Device, pattern: Option<&DashPattern>) -> Self {
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Dash buffer"),
+            contents: bytemuck::cast_slice(&[DashUniform::from(pattern)]),
+            usage: BufferUsage::UNIFORM,
+        });
+
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Dash bind group layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Dash bind group"),
+            layout: &layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        DashBinding {
+            _buffer: buffer,
+            layout,
+            bind_group,
+        }
+    }
+}
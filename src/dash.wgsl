@@ -0,0 +1,54 @@
+// Dash patterns for stroke shaders, which include this file before their
+// own source. `Dash` must match `DashUniform` in dash.rs.
+
+[[block]]
+struct Dash {
+    // (start, end) of up to four dashes within one period of the pattern.
+    intervals: array<vec4<f32>, 2>;
+    count: u32;
+    pixels: u32;
+    offset: f32;
+    period: f32;
+};
+
+[[group(1), binding(0)]]
+var dash: Dash;
+
+// Signed distance from `position` to the nearest copy of the interval from
+// `start` to `end` in a pattern repeating every `period`.
+fn interval_distance(position: f32, start: f32, end: f32, period: f32) -> f32 {
+    let here: f32 = max(start - position, position - end);
+    let before: f32 = max(start - period - position, position - end + period);
+    let after: f32 = max(start + period - position, position - end - period);
+
+    return min(here, min(before, after));
+}
+
+// Signed distance along a stroke from `along` to the nearest dash, negative
+// inside one, in scene units.
+fn dash_distance(along: f32, scene_per_pixel: f32) -> f32 {
+    if (dash.count == 0u) {
+        return -3.40282347e38;
+    }
+
+    var scale: f32 = 1.;
+    if (dash.pixels != 0u) {
+        scale = scene_per_pixel;
+    }
+
+    let position: f32 = along / scale + dash.offset;
+    let p: f32 = position - dash.period * floor(position / dash.period);
+
+    var nearest: f32 = interval_distance(p, dash.intervals[0].x, dash.intervals[0].y, dash.period);
+    if (dash.count > 1u) {
+        nearest = min(nearest, interval_distance(p, dash.intervals[0].z, dash.intervals[0].w, dash.period));
+    }
+    if (dash.count > 2u) {
+        nearest = min(nearest, interval_distance(p, dash.intervals[1].x, dash.intervals[1].y, dash.period));
+    }
+    if (dash.count > 3u) {
+        nearest = min(nearest, interval_distance(p, dash.intervals[1].z, dash.intervals[1].w, dash.period));
+    }
+
+    return nearest * scale;
+}
@@ -0,0 +1,59 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    // Position in the ellipse's own frame, scaled so that the edge is at
+    // distance 1 from the center.
+    [[location(1)]] coord: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(-1., -1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., 1.),
+);
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] radii: vec2<f32>,
+    [[location(2)]] rotation: f32,
+    [[location(3)]] color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let local: vec2<f32> = corners[in_vertex_index] * radii;
+    let c: f32 = cos(rotation);
+    let s: f32 = sin(rotation);
+    let offset: vec2<f32> = vec2<f32>(c * local.x - s * local.y, s * local.x + c * local.y);
+
+    out.color = color;
+    out.coord = corners[in_vertex_index];
+    out.position = uniforms.transform * vec4<f32>(position + offset, 0., 1.);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // Dividing by the screen-space rate of change of `f` approximates the
+    // distance to the edge in pixels.
+    let f: f32 = length(in.coord) - 1.;
+    let alpha: f32 = clamp(0.5 - f / fwidth(f), 0., 1.);
+
+    if (alpha < 0.01) {
+        discard;
+    }
+
+    return in.color * alpha;
+}
@@ -0,0 +1,143 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum Error {
+    /// No adapter is compatible with the requested surface and backends.
+    NoAdapter,
+    RequestDevice(wgpu::RequestDeviceError),
+    CreateWindow(winit::error::OsError),
+    /// A rendered texture could not be mapped for reading.
+    ReadBack,
+    /// A texture could not be read back because its pixels are not four
+    /// bytes of red, green, blue and alpha in some order.
+    UnsupportedFormat(wgpu::TextureFormat),
+    Io(io::Error),
+    EncodePng(png::EncodingError),
+    DecodePng(png::DecodingError),
+    /// Font data could not be parsed as a TrueType or OpenType font.
+    InvalidFont,
+    /// A recording's frame rate was not a positive, finite number.
+    InvalidFrameRate(f64),
+    /// A sprite referred to an image that is not in its layer.
+    MissingImage(u32),
+    /// An image's data was not `width * height * 4` bytes long.
+    ImageDataLength {
+        expected: usize,
+        actual: usize,
+    },
+    /// An image was empty, or larger than a texture can be.
+    ImageSize {
+        width: u32,
+        height: u32,
+    },
+    /// A mesh's number of indices was not a multiple of three.
+    MeshIndexCount(usize),
+    /// A mesh index referred to a vertex that is not in the mesh.
+    MissingVertex(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NoAdapter => write!(f, "no compatible graphics adapter found"),
+            Error::RequestDevice(e) => write!(f, "failed to request graphics device: {}", e),
+            Error::CreateWindow(e) => write!(f, "failed to create window: {}", e),
+            Error::ReadBack => write!(f, "failed to read rendered frame back from the GPU"),
+            Error::UnsupportedFormat(format) => {
+                write!(f, "can't read back frames in the {:?} format", format)
+            }
+            Error::Io(e) => write!(f, "{}", e),
+            Error::EncodePng(e) => write!(f, "failed to write PNG: {}", e),
+            Error::DecodePng(e) => write!(f, "failed to read PNG: {}", e),
+            Error::InvalidFont => write!(f, "failed to parse font"),
+            Error::InvalidFrameRate(rate) => {
+                write!(f, "frame rate must be positive and finite, got {}", rate)
+            }
+            Error::MissingImage(index) => {
+                write!(
+                    f,
+                    "sprite refers to image {}, which is not in the layer",
+                    index
+                )
+            }
+            Error::ImageDataLength { expected, actual } => write!(
+                f,
+                "image data is {} bytes long, expected {}",
+                actual, expected
+            ),
+            Error::ImageSize { width, height } => write!(
+                f,
+                "image is {}x{}, sides must be between 1 and {}",
+                width,
+                height,
+                wgpu::Limits::default().max_texture_dimension_2d
+            ),
+            Error::MeshIndexCount(count) => write!(
+                f,
+                "mesh has {} indices, which is not a multiple of three",
+                count
+            ),
+            Error::MissingVertex(index) => {
+                write!(f, "mesh index {} is not a vertex in the mesh", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::RequestDevice(e) => Some(e),
+            Error::CreateWindow(e) => Some(e),
+            Error::Io(e) => Some(e),
+            Error::EncodePng(e) => Some(e),
+            Error::DecodePng(e) => Some(e),
+            Error::NoAdapter
+            | Error::ReadBack
+            | Error::UnsupportedFormat(_)
+            | Error::InvalidFont
+            | Error::InvalidFrameRate(_)
+            | Error::MissingImage(_)
+            | Error::ImageDataLength { .. }
+            | Error::ImageSize { .. }
+            | Error::MeshIndexCount(_)
+            | Error::MissingVertex(_) => None,
+        }
+    }
+}
+
+impl From<wgpu::RequestDeviceError> for Error {
+    fn from(e: wgpu::RequestDeviceError) -> Self {
+        Error::RequestDevice(e)
+    }
+}
+
+impl From<winit::error::OsError> for Error {
+    fn from(e: winit::error::OsError) -> Self {
+        Error::CreateWindow(e)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<png::EncodingError> for Error {
+    fn from(e: png::EncodingError) -> Self {
+        Error::EncodePng(e)
+    }
+}
+
+impl From<png::DecodingError> for Error {
+    fn from(e: png::DecodingError) -> Self {
+        Error::DecodePng(e)
+    }
+}
+
+impl From<ab_glyph::InvalidFont> for Error {
+    fn from(_: ab_glyph::InvalidFont) -> Self {
+        Error::InvalidFont
+    }
+}
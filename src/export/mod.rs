@@ -0,0 +1,122 @@
+use crate::dash::{DashPattern, DashUnits};
+use crate::headless::Image;
+use crate::units::WidthUnits;
+use crate::zoom::Camera;
+use crate::{
+    Arc, ArrowStyle, Circle, Color, Curve, Ellipse, Font, Hairline, Line, LineCap, Marker,
+    MeshVertex, Orientation, Polygon, Polyline, Rectangle, Sprite, Text,
+};
+
+pub mod pdf;
+pub mod svg;
+
+/// Receives the shape data of a layer tree in painter's order.
+///
+/// Layers call the method matching their shape type from
+/// `Layer::visit_shapes`; layers that don't override it are skipped.
+pub trait ShapeVisitor {
+    fn circles(&mut self, _data: &[Circle], _units: WidthUnits) {}
+
+    fn ellipses(&mut self, _data: &[Ellipse]) {}
+
+    fn arcs(&mut self, _data: &[Arc], _units: WidthUnits) {}
+
+    fn rectangles(&mut self, _data: &[Rectangle], _units: WidthUnits) {}
+
+    fn lines(
+        &mut self,
+        _data: &[Line],
+        _cap: LineCap,
+        _dash: Option<&DashPattern>,
+        _units: WidthUnits,
+    ) {
+    }
+
+    fn arrows(&mut self, _data: &[Line], _style: &ArrowStyle, _units: WidthUnits) {}
+
+    fn hairlines(&mut self, _data: &[Hairline], _units: WidthUnits) {}
+
+    fn polygons(&mut self, _data: &[Polygon]) {}
+
+    fn polylines(&mut self, _data: &[Polyline], _dash: Option<&DashPattern>) {}
+
+    fn curves(&mut self, _data: &[Curve], _dash: Option<&DashPattern>) {}
+
+    fn texts(&mut self, _data: &[Text], _font: &Font) {}
+
+    fn images(&mut self, _images: &[Image], _data: &[Sprite]) {}
+
+    fn markers(&mut self, _data: &[Marker]) {}
+
+    fn mesh(&mut self, _vertices: &[MeshVertex], _indices: &[u32]) {}
+}
+
+/// Maps scene coordinates to output coordinates for a given camera, with the
+/// origin in the upper-left corner and Y increasing going down.
+pub(crate) struct Projection {
+    width: f32,
+    height: f32,
+    camera: Camera,
+}
+
+impl Projection {
+    pub fn new(width: f32, height: f32, camera: Camera) -> Self {
+        Projection {
+            width,
+            height,
+            camera,
+        }
+    }
+
+    fn scale(&self) -> f32 {
+        self.camera.scale / 2.
+    }
+
+    pub fn point(&self, point: [f32; 2]) -> [f32; 2] {
+        [
+            self.width / 2. + self.scale() * (point[0] - self.camera.center[0]),
+            self.height / 2. - self.scale() * (point[1] - self.camera.center[1]),
+        ]
+    }
+
+    pub fn length(&self, length: f32) -> f32 {
+        self.scale() * length
+    }
+
+    /// Returns a stroke width in output units.
+    pub fn width(&self, width: f32, units: WidthUnits) -> f32 {
+        match units {
+            WidthUnits::Scene => self.length(width),
+            WidthUnits::Pixels => width,
+        }
+    }
+
+    /// Returns the dash and gap lengths and the offset of a dash pattern in
+    /// output units.
+    pub fn dash(&self, pattern: &DashPattern) -> (Vec<f32>, f32) {
+        let scale = match pattern.units() {
+            DashUnits::Scene => self.scale(),
+            DashUnits::Pixels => 1.,
+        };
+        let dashes = pattern.dashes().iter().map(|d| d * scale).collect();
+        (dashes, pattern.offset() * scale)
+    }
+
+    /// Returns the endpoints and full width of a hairline, clipped to the
+    /// output.
+    pub fn hairline(&self, hairline: &Hairline, units: WidthUnits) -> ([f32; 2], [f32; 2], f32) {
+        let Projection { width, height, .. } = *self;
+        let [x, y] = self.point([hairline.location, hairline.location]);
+        let line_width = self.width(2. * hairline.width, units);
+
+        match hairline.orientation {
+            Orientation::Vertical => ([x, 0.], [x, height], line_width),
+            Orientation::Horizontal => ([0., y], [width, y], line_width),
+        }
+    }
+}
+
+pub(crate) fn hex_color(color: Color) -> (String, f32) {
+    let [r, g, b, a] = color.to_rgba();
+    (format!("#{:02x}{:02x}{:02x}", r, g, b), a as f32 / 255.)
+}
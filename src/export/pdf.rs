@@ -0,0 +1,768 @@
+use std::collections::BTreeSet;
+use std::f32::consts::FRAC_PI_2;
+use std::fmt::Write;
+
+use ab_glyph::{point, OutlineCurve, Point};
+use winit::dpi::PhysicalSize;
+
+use crate::dash::DashPattern;
+use crate::export::{Projection, ShapeVisitor};
+use crate::layer::Layer;
+use crate::mesh::flat_triangles;
+use crate::units::WidthUnits;
+use crate::zoom::Camera;
+use crate::{
+    Arc, ArrowHead, ArrowStyle, Bezier, Circle, Color, Curve, Ellipse, FillRule, Font, Hairline,
+    Line, LineCap, LineJoin, Marker, MeshVertex, Polygon, Polyline, Rectangle, Symbol, Text,
+};
+
+/// Control point distance for approximating a quarter circle with a cubic.
+const KAPPA: f32 = 0.552_284_8;
+
+struct PdfWriter {
+    projection: Projection,
+    content: String,
+    alphas: BTreeSet<u8>,
+}
+
+impl PdfWriter {
+    /// Runs `draw` to add one shape to the content stream, dropping what it
+    /// wrote if any of its numbers isn't finite, since PDF has no way to
+    /// write those.
+    fn shape(&mut self, draw: impl FnOnce(&mut Self)) {
+        let start = self.content.len();
+        let alphas = self.alphas.clone();
+        draw(self);
+
+        let non_finite = self.content[start..]
+            .split(|c: char| c.is_whitespace() || c == '[' || c == ']')
+            .any(|token| matches!(token.parse::<f32>(), Ok(n) if !n.is_finite()));
+        if non_finite {
+            self.content.truncate(start);
+            self.alphas = alphas;
+        }
+    }
+
+    /// Returns half the width of a stroke in scene units, or zero if there
+    /// is no stroke.
+    fn inset(&self, stroke_width: f32, units: WidthUnits) -> f32 {
+        if stroke_width > 0. {
+            self.projection.width(stroke_width, units) / self.projection.length(1.)
+        } else {
+            0.
+        }
+    }
+
+    fn paint(&mut self, operator: &str, color: Color) {
+        let [r, g, b, a] = color.to_rgba();
+        writeln!(
+            self.content,
+            "/A{} gs {} {} {} {}",
+            a,
+            r as f32 / 255.,
+            g as f32 / 255.,
+            b as f32 / 255.,
+            operator
+        )
+        .unwrap();
+        self.alphas.insert(a);
+    }
+
+    /// Sets the dash pattern for following strokes, or solid strokes if
+    /// `dash` is `None`.
+    fn dash(&mut self, dash: Option<&DashPattern>) {
+        let (dashes, offset) = match dash {
+            Some(dash) => self.projection.dash(dash),
+            None => (Vec::new(), 0.),
+        };
+        let dashes: Vec<String> = dashes.iter().map(|d| d.to_string()).collect();
+        writeln!(self.content, "[{}] {} d", dashes.join(" "), offset).unwrap();
+    }
+
+    fn line(
+        &mut self,
+        start: [f32; 2],
+        end: [f32; 2],
+        width: f32,
+        cap: LineCap,
+        dash: Option<&DashPattern>,
+        color: Color,
+    ) {
+        let cap = match cap {
+            LineCap::Butt => 0,
+            LineCap::Round => 1,
+            LineCap::Square => 2,
+        };
+
+        self.paint("RG", color);
+        self.dash(dash);
+        writeln!(
+            self.content,
+            "{} w {} J {} {} m {} {} l S",
+            width, cap, start[0], start[1], end[0], end[1]
+        )
+        .unwrap();
+    }
+
+    /// Adds an ellipse to the current path, approximated by four cubics.
+    /// All arguments are in scene coordinates.
+    fn ellipse_path(&mut self, center: [f32; 2], [rx, ry]: [f32; 2], rotation: f32) {
+        let (sin, cos) = rotation.sin_cos();
+        let point = |x: f32, y: f32| {
+            self.projection.point([
+                center[0] + cos * x * rx - sin * y * ry,
+                center[1] + sin * x * rx + cos * y * ry,
+            ])
+        };
+        let k = KAPPA;
+
+        let start = point(1., 0.);
+        let curves = [
+            (point(1., k), point(k, 1.), point(0., 1.)),
+            (point(-k, 1.), point(-1., k), point(-1., 0.)),
+            (point(-1., -k), point(-k, -1.), point(0., -1.)),
+            (point(k, -1.), point(1., -k), point(1., 0.)),
+        ];
+
+        writeln!(self.content, "{} {} m", start[0], start[1]).unwrap();
+        for &(c1, c2, p) in &curves {
+            writeln!(
+                self.content,
+                "{} {} {} {} {} {} c",
+                c1[0], c1[1], c2[0], c2[1], p[0], p[1]
+            )
+            .unwrap();
+        }
+    }
+
+    /// Continues the current path from the start of `arc` along it to its
+    /// end, approximated by cubics spanning at most a quarter turn each.
+    fn arc_path(&mut self, arc: &Arc) {
+        let (start, end) = arc.angles();
+        let segments = ((end - start).abs() / FRAC_PI_2).ceil().max(1.) as u32;
+        let step = (end - start) / segments as f32;
+        // Distance of the control points from the ends of each cubic, along
+        // the tangents, relative to the radius.
+        let k = 4. / 3. * (step / 4.).tan();
+        let projection = &self.projection;
+        let point = |angle: f32, tangent: f32| {
+            let (sin, cos) = angle.sin_cos();
+            let [x, y] = arc.point(angle);
+            projection.point([
+                x - tangent * sin * arc.radius,
+                y + tangent * cos * arc.radius,
+            ])
+        };
+
+        let curves: Vec<_> = (0..segments)
+            .map(|i| {
+                let a0 = start + step * i as f32;
+                let a1 = a0 + step;
+                (point(a0, k), point(a1, -k), point(a1, 0.))
+            })
+            .collect();
+
+        for (c1, c2, p) in curves {
+            writeln!(
+                self.content,
+                "{} {} {} {} {} {} c",
+                c1[0], c1[1], c2[0], c2[1], p[0], p[1]
+            )
+            .unwrap();
+        }
+    }
+
+    /// Adds a rectangle to the current path, with its corners rounded by
+    /// quarter circles approximated by cubics. Each side is moved `inset`
+    /// scene units towards the center, shrinking the corners to match.
+    fn rectangle_path(&mut self, rectangle: &Rectangle, inset: f32) {
+        let [x1, y1] = rectangle.upper_left;
+        let [x2, y2] = rectangle.bottom_right;
+        let center = [(x1 + x2) / 2., (y1 + y2) / 2.];
+        let (hx, hy) = ((x2 - x1).abs() / 2., (y2 - y1).abs() / 2.);
+        let r = rectangle.corner_radius.max(0.).min(hx.min(hy));
+        let (hx, hy, r) = (hx - inset, hy - inset, (r - inset).max(0.));
+        let k = r * (1. - KAPPA);
+
+        let (sin, cos) = rectangle.rotation.sin_cos();
+        let point = |x: f32, y: f32| {
+            self.projection
+                .point([center[0] + cos * x - sin * y, center[1] + sin * x + cos * y])
+        };
+
+        // Each side as a straight line, followed by the corner it leads to
+        // as (control point, control point, end point).
+        let start = point(-hx + r, -hy);
+        let sides = [
+            (
+                point(hx - r, -hy),
+                (point(hx - k, -hy), point(hx, -hy + k), point(hx, -hy + r)),
+            ),
+            (
+                point(hx, hy - r),
+                (point(hx, hy - k), point(hx - k, hy), point(hx - r, hy)),
+            ),
+            (
+                point(-hx + r, hy),
+                (point(-hx + k, hy), point(-hx, hy - k), point(-hx, hy - r)),
+            ),
+            (
+                point(-hx, -hy + r),
+                (point(-hx, -hy + k), point(-hx + k, -hy), start),
+            ),
+        ];
+
+        writeln!(self.content, "{} {} m", start[0], start[1]).unwrap();
+        for &(line, (c1, c2, p)) in &sides {
+            writeln!(self.content, "{} {} l", line[0], line[1]).unwrap();
+            if r > 0. {
+                writeln!(
+                    self.content,
+                    "{} {} {} {} {} {} c",
+                    c1[0], c1[1], c2[0], c2[1], p[0], p[1]
+                )
+                .unwrap();
+            }
+        }
+        self.content.push_str("h\n");
+    }
+}
+
+impl ShapeVisitor for PdfWriter {
+    fn circles(&mut self, data: &[Circle], units: WidthUnits) {
+        for circle in data {
+            self.shape(|w| {
+                let center = circle.position;
+                let radii = [circle.radius, circle.radius];
+                let inset = w.inset(circle.stroke_width, units);
+
+                // As on the GPU, the fill stops at the inner edge of the
+                // stroke so it doesn't show through a translucent stroke.
+                if circle.radius > inset {
+                    let inner = circle.radius - inset;
+                    w.paint("rg", circle.color);
+                    w.ellipse_path(center, [inner, inner], 0.);
+                    w.content.push_str("f\n");
+                }
+
+                if circle.stroke_width > 0. {
+                    w.paint("RG", circle.stroke_color);
+                    w.dash(None);
+                    let width = w.projection.width(2. * circle.stroke_width, units);
+                    writeln!(w.content, "{} w", width).unwrap();
+                    w.ellipse_path(center, radii, 0.);
+                    w.content.push_str("S\n");
+                }
+            });
+        }
+    }
+
+    fn arcs(&mut self, data: &[Arc], units: WidthUnits) {
+        for arc in data {
+            self.shape(|w| {
+                let [x0, y0] = w.projection.point(arc.point(arc.start_angle));
+
+                if arc.stroke_width > 0. {
+                    w.paint("RG", arc.color);
+                    w.dash(None);
+                    let width = w.projection.width(2. * arc.stroke_width, units);
+                    writeln!(w.content, "{} w 0 J", width).unwrap();
+                    writeln!(w.content, "{} {} m", x0, y0).unwrap();
+                    w.arc_path(arc);
+                    w.content.push_str("S\n");
+                } else {
+                    let [cx, cy] = w.projection.point(arc.center);
+                    w.paint("rg", arc.color);
+                    writeln!(w.content, "{} {} m {} {} l", cx, cy, x0, y0).unwrap();
+                    w.arc_path(arc);
+                    w.content.push_str("h f\n");
+                }
+            });
+        }
+    }
+
+    fn ellipses(&mut self, data: &[Ellipse]) {
+        for ellipse in data {
+            self.shape(|w| {
+                w.paint("rg", ellipse.color);
+                w.ellipse_path(ellipse.position, ellipse.radii, ellipse.rotation);
+                w.content.push_str("f\n");
+            });
+        }
+    }
+
+    fn rectangles(&mut self, data: &[Rectangle], units: WidthUnits) {
+        for rectangle in data {
+            self.shape(|w| {
+                // Like circles, the fill is inset to the stroke's inner edge.
+                let inset = w.inset(rectangle.stroke_width, units);
+                let [x1, y1] = rectangle.upper_left;
+                let [x2, y2] = rectangle.bottom_right;
+                if (x2 - x1).abs().min((y2 - y1).abs()) > 2. * inset {
+                    w.paint("rg", rectangle.color);
+                    w.rectangle_path(rectangle, inset);
+                    w.content.push_str("f\n");
+                }
+
+                if rectangle.stroke_width > 0. {
+                    w.paint("RG", rectangle.stroke_color);
+                    w.dash(None);
+                    let width = w.projection.width(2. * rectangle.stroke_width, units);
+                    writeln!(w.content, "{} w 0 j", width).unwrap();
+                    w.rectangle_path(rectangle, 0.);
+                    w.content.push_str("S\n");
+                }
+            });
+        }
+    }
+
+    fn lines(
+        &mut self,
+        data: &[Line],
+        cap: LineCap,
+        dash: Option<&DashPattern>,
+        units: WidthUnits,
+    ) {
+        for line in data {
+            self.shape(|w| {
+                // The shader extrudes `width` to each side of the line.
+                let width = w.projection.width(2. * line.width, units);
+                let start = w.projection.point(line.start);
+                let end = w.projection.point(line.end);
+                w.line(start, end, width, cap, dash, line.color);
+            });
+        }
+    }
+
+    fn arrows(&mut self, data: &[Line], style: &ArrowStyle, units: WidthUnits) {
+        let scale = self.projection.width(1., units);
+        for line in data {
+            self.shape(|w| {
+                let start = w.projection.point(line.start);
+                let end = w.projection.point(line.end);
+
+                if style.head != ArrowHead::Open {
+                    w.paint("rg", line.color);
+                    for (i, [x, y]) in style
+                        .outline(start, end, line.width, scale)
+                        .iter()
+                        .enumerate()
+                    {
+                        let operator = if i == 0 { "m" } else { "l" };
+                        writeln!(w.content, "{} {} {}", x, y, operator).unwrap();
+                    }
+                    w.content.push_str("h f\n");
+                    return;
+                }
+
+                let width = 2. * line.width * scale;
+                w.line(start, end, width, LineCap::Butt, None, line.color);
+                for [a, b, c] in style.barbs(start, end, scale) {
+                    writeln!(
+                        w.content,
+                        "1 J 1 j {} {} m {} {} l {} {} l S",
+                        a[0], a[1], b[0], b[1], c[0], c[1]
+                    )
+                    .unwrap();
+                }
+            });
+        }
+    }
+
+    fn hairlines(&mut self, data: &[Hairline], units: WidthUnits) {
+        for hairline in data {
+            self.shape(|w| {
+                let (start, end, width) = w.projection.hairline(hairline, units);
+                w.line(start, end, width, LineCap::Butt, None, hairline.color);
+            });
+        }
+    }
+
+    fn polygons(&mut self, data: &[Polygon]) {
+        for polygon in data {
+            self.shape(|w| {
+                if polygon.rings.iter().all(|ring| ring.is_empty()) {
+                    return;
+                }
+
+                w.paint("rg", polygon.color);
+                for ring in polygon.rings.iter().filter(|ring| !ring.is_empty()) {
+                    for (i, &point) in ring.iter().enumerate() {
+                        let [x, y] = w.projection.point(point);
+                        let operator = if i == 0 { "m" } else { "l" };
+                        writeln!(w.content, "{} {} {}", x, y, operator).unwrap();
+                    }
+                    w.content.push_str("h\n");
+                }
+                w.content.push_str(match polygon.fill_rule {
+                    FillRule::NonZero => "f\n",
+                    FillRule::EvenOdd => "f*\n",
+                });
+            });
+        }
+    }
+
+    fn polylines(&mut self, data: &[Polyline], dash: Option<&DashPattern>) {
+        for polyline in data {
+            self.shape(|w| {
+                if polyline.points.len() < 2 {
+                    return;
+                }
+
+                let join = match polyline.join {
+                    LineJoin::Miter { limit } => format!("0 j {} M", limit.max(1.)),
+                    LineJoin::Round => "1 j".to_string(),
+                    LineJoin::Bevel => "2 j".to_string(),
+                };
+
+                w.paint("RG", polyline.color);
+                w.dash(dash);
+                writeln!(
+                    w.content,
+                    "{} w 0 J {}",
+                    w.projection.length(2. * polyline.width),
+                    join
+                )
+                .unwrap();
+                for (i, &point) in polyline.points.iter().enumerate() {
+                    let [x, y] = w.projection.point(point);
+                    let operator = if i == 0 { "m" } else { "l" };
+                    writeln!(w.content, "{} {} {}", x, y, operator).unwrap();
+                }
+                w.content.push_str("S\n");
+            });
+        }
+    }
+
+    fn curves(&mut self, data: &[Curve], dash: Option<&DashPattern>) {
+        for curve in data {
+            self.shape(|w| {
+                // PDF only has cubic curves, so quadratics are raised to cubics
+                // with the same shape.
+                let [p0, p1, p2, p3] = match curve.bezier {
+                    Bezier::Quadratic([p0, p1, p2]) => [
+                        p0,
+                        [
+                            p0[0] + 2. / 3. * (p1[0] - p0[0]),
+                            p0[1] + 2. / 3. * (p1[1] - p0[1]),
+                        ],
+                        [
+                            p2[0] + 2. / 3. * (p1[0] - p2[0]),
+                            p2[1] + 2. / 3. * (p1[1] - p2[1]),
+                        ],
+                        p2,
+                    ],
+                    Bezier::Cubic(points) => points,
+                };
+                let [x0, y0] = w.projection.point(p0);
+                let [x1, y1] = w.projection.point(p1);
+                let [x2, y2] = w.projection.point(p2);
+                let [x3, y3] = w.projection.point(p3);
+
+                w.paint("RG", curve.color);
+                w.dash(dash);
+                writeln!(
+                    w.content,
+                    "{} w 0 J 1 j",
+                    w.projection.length(2. * curve.width)
+                )
+                .unwrap();
+                writeln!(w.content, "{} {} m", x0, y0).unwrap();
+                writeln!(w.content, "{} {} {} {} {} {} c S", x1, y1, x2, y2, x3, y3).unwrap();
+            });
+        }
+    }
+
+    fn markers(&mut self, data: &[Marker]) {
+        for marker in data {
+            self.shape(|w| {
+                w.paint("rg", marker.color);
+                if marker.symbol == Symbol::Circle {
+                    // Marker sizes are in output units, but `ellipse_path` takes
+                    // scene units.
+                    let radius = marker.size / 2. / w.projection.length(1.);
+                    w.ellipse_path(marker.position, [radius, radius], 0.);
+                } else {
+                    let [cx, cy] = w.projection.point(marker.position);
+                    for (i, [x, y]) in marker.symbol.outline(marker.size).iter().enumerate() {
+                        let operator = if i == 0 { "m" } else { "l" };
+                        writeln!(w.content, "{} {} {}", cx + x, cy - y, operator).unwrap();
+                    }
+                    w.content.push_str("h\n");
+                }
+                w.content.push_str("f\n");
+            });
+        }
+    }
+
+    fn texts(&mut self, data: &[Text], font: &Font) {
+        // Glyphs are drawn as filled outlines, so the document doesn't need
+        // the font embedded.
+        for text in data {
+            self.shape(|w| {
+                let curves = text.outline(font);
+                if curves.is_empty() {
+                    return;
+                }
+
+                w.paint("rg", text.color);
+                let mut current = None;
+                for curve in curves {
+                    let (start, end) = match curve {
+                        OutlineCurve::Line(a, b) | OutlineCurve::Quad(a, _, b) => (a, b),
+                        OutlineCurve::Cubic(a, _, _, b) => (a, b),
+                    };
+                    if current != Some(start) {
+                        if current.is_some() {
+                            w.content.push_str("h\n");
+                        }
+                        let [x, y] = w.projection.point([start.x, start.y]);
+                        writeln!(w.content, "{} {} m", x, y).unwrap();
+                    }
+                    current = Some(end);
+
+                    // As with curves, quadratics are raised to cubics.
+                    let raise = |p: Point, control: Point| {
+                        point(
+                            p.x + 2. / 3. * (control.x - p.x),
+                            p.y + 2. / 3. * (control.y - p.y),
+                        )
+                    };
+                    let controls = match curve {
+                        OutlineCurve::Line(..) => None,
+                        OutlineCurve::Quad(a, b, c) => Some((raise(a, b), raise(c, b))),
+                        OutlineCurve::Cubic(_, b, c, _) => Some((b, c)),
+                    };
+                    let [x, y] = w.projection.point([end.x, end.y]);
+                    match controls {
+                        None => writeln!(w.content, "{} {} l", x, y).unwrap(),
+                        Some((c1, c2)) => {
+                            let [x1, y1] = w.projection.point([c1.x, c1.y]);
+                            let [x2, y2] = w.projection.point([c2.x, c2.y]);
+                            writeln!(w.content, "{} {} {} {} {} {} c", x1, y1, x2, y2, x, y)
+                                .unwrap();
+                        }
+                    }
+                }
+                w.content.push_str("h f\n");
+            });
+        }
+    }
+
+    fn mesh(&mut self, vertices: &[MeshVertex], indices: &[u32]) {
+        // As in SVG, each triangle is filled with the average of the colors
+        // of its corners.
+        for (corners, color) in flat_triangles(vertices, indices) {
+            self.shape(|w| {
+                w.paint("rg", color);
+                for (i, &point) in corners.iter().enumerate() {
+                    let [x, y] = w.projection.point(point);
+                    let operator = if i == 0 { "m" } else { "l" };
+                    writeln!(w.content, "{} {} {}", x, y, operator).unwrap();
+                }
+                w.content.push_str("h f\n");
+            });
+        }
+    }
+}
+
+/// Serializes the shapes of `layer` into a single-page PDF document.
+///
+/// The page is `page_width` by `page_height` points, and `camera` maps the
+/// scene onto it the same way it maps the scene onto a window of that many
+/// pixels. Everything is clipped to the page, including hairlines. Text is
+/// drawn as glyph outlines, and images are not exported.
+pub fn to_pdf<T: Layer>(layer: &T, page_width: f32, page_height: f32, camera: Camera) -> Vec<u8> {
+    let mut writer = PdfWriter {
+        projection: Projection::new(page_width, page_height, camera),
+        content: String::new(),
+        alphas: BTreeSet::new(),
+    };
+
+    // Flip the page so that Y increases going down, matching `Projection`,
+    // and clip to the page itself.
+    writeln!(
+        writer.content,
+        "1 0 0 -1 0 {1} cm 0 0 {0} {1} re W n 0 J",
+        page_width, page_height
+    )
+    .unwrap();
+    let size = PhysicalSize::new(page_width.round() as u32, page_height.round() as u32);
+    layer.visit_shapes(size, &mut writer);
+
+    let mut ext_g_states = String::new();
+    for alpha in &writer.alphas {
+        let alpha_f = *alpha as f32 / 255.;
+        write!(
+            ext_g_states,
+            "/A{} << /ca {} /CA {} >> ",
+            alpha, alpha_f, alpha_f
+        )
+        .unwrap();
+    }
+
+    let content = writer.content.trim_end();
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R /Resources << /ExtGState << {}>> >> >>",
+            page_width, page_height, ext_g_states
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ),
+    ];
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        writeln!(out, "{} 0 obj\n{}\nendobj", i + 1, object).unwrap();
+    }
+
+    let xref = out.len();
+    writeln!(out, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1).unwrap();
+    for offset in offsets {
+        writeln!(out, "{:010} 00000 n ", offset).unwrap();
+    }
+    write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    )
+    .unwrap();
+
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CirclesLayer, GenericLayer, GroupLayer, RectanglesLayer};
+
+    const RED: Color = Color(u32::from_ne_bytes([0xff, 0, 0, 0xff]));
+    const HALF_BLUE: Color = Color(u32::from_ne_bytes([0, 0, 0xff, 0x80]));
+
+    /// Exports `layers` onto a 200 by 100 point page, where the default
+    /// camera maps the scene origin to (100, 50) and two scene units to one
+    /// point.
+    fn export(layers: Vec<Box<dyn GenericLayer>>) -> String {
+        let pdf = to_pdf(&GroupLayer::new(layers), 200., 100., Camera::default());
+        String::from_utf8(pdf).unwrap()
+    }
+
+    /// Returns the content stream of a document, checking its `/Length`.
+    fn content(pdf: &str) -> &str {
+        let start = pdf.find("<< /Length ").unwrap() + "<< /Length ".len();
+        let (length, rest) = pdf[start..].split_once(" >>\nstream\n").unwrap();
+        let length: usize = length.parse().unwrap();
+        assert_eq!(&rest[length..length + "\nendstream".len()], "\nendstream");
+        &rest[..length]
+    }
+
+    fn circle(position: [f32; 2], color: Color) -> Circle {
+        Circle {
+            position,
+            color,
+            radius: 10.,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn xref_offsets() {
+        let pdf = export(vec![Box::new(CirclesLayer::new(vec![circle(
+            [0., 0.],
+            RED,
+        )]))]);
+
+        let startxref = pdf.rfind("startxref\n").unwrap() + "startxref\n".len();
+        let xref: usize = pdf[startxref..].lines().next().unwrap().parse().unwrap();
+        let mut lines = pdf[xref..].lines();
+        assert_eq!(lines.next(), Some("xref"));
+        assert_eq!(lines.next(), Some("0 5"));
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+        for object in 1..5 {
+            let entry = lines.next().unwrap();
+            assert!(entry.ends_with(" 00000 n "));
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", object)));
+        }
+        assert_eq!(lines.next(), Some("trailer"));
+    }
+
+    #[test]
+    fn clipped_to_page() {
+        let pdf = export(Vec::new());
+        assert_eq!(content(&pdf), "1 0 0 -1 0 100 cm 0 0 200 100 re W n 0 J");
+    }
+
+    #[test]
+    fn alphas_deduplicated() {
+        let pdf = export(vec![
+            Box::new(CirclesLayer::new(vec![
+                circle([0., 0.], HALF_BLUE),
+                circle([20., 0.], RED),
+            ])),
+            Box::new(CirclesLayer::new(vec![circle([40., 0.], HALF_BLUE)])),
+        ]);
+
+        let content = content(&pdf);
+        assert_eq!(content.matches("/A128 gs").count(), 2);
+        assert_eq!(content.matches("/A255 gs").count(), 1);
+        assert_eq!(pdf.matches("/A128 <<").count(), 1);
+        assert_eq!(pdf.matches("/A255 <<").count(), 1);
+        assert!(pdf.contains(
+            "/ExtGState << /A128 << /ca 0.5019608 /CA 0.5019608 >> /A255 << /ca 1 /CA 1 >> >>"
+        ));
+    }
+
+    #[test]
+    fn non_finite_shapes_skipped() {
+        let pdf = export(vec![Box::new(CirclesLayer::new(vec![
+            circle([f32::NAN, 0.], HALF_BLUE),
+            circle([f32::INFINITY, 0.], HALF_BLUE),
+            circle([0., 0.], RED),
+        ]))]);
+
+        let content = content(&pdf);
+        assert!(!content.contains("NaN"));
+        assert!(!content.contains("inf"));
+        assert!(content.contains("/A255 gs"));
+        assert!(!pdf.contains("/A128"));
+    }
+
+    #[test]
+    fn fill_inside_stroke() {
+        let circle = Circle {
+            color: RED,
+            radius: 20.,
+            stroke_width: 4.,
+            stroke_color: HALF_BLUE,
+            ..Default::default()
+        };
+        let rectangle = Rectangle {
+            upper_left: [-40., 20.],
+            bottom_right: [40., -20.],
+            color: RED,
+            stroke_width: 2.,
+            stroke_color: HALF_BLUE,
+            ..Default::default()
+        };
+        let pdf = export(vec![
+            Box::new(CirclesLayer::new(vec![circle])),
+            Box::new(RectanglesLayer::new(vec![rectangle])),
+        ]);
+
+        // Each path starts with its move-to, and the fill comes first.
+        let paths: Vec<&str> = content(&pdf)
+            .lines()
+            .filter(|line| line.ends_with(" m"))
+            .collect();
+        assert_eq!(paths, ["108 50 m", "110 50 m", "81 59 m", "80 60 m"]);
+    }
+}
@@ -0,0 +1,714 @@
+use std::fmt::Write;
+
+use winit::dpi::PhysicalSize;
+
+use crate::dash::DashPattern;
+use crate::export::{hex_color, Projection, ShapeVisitor};
+use crate::layer::Layer;
+use crate::mesh::flat_triangles;
+use crate::units::WidthUnits;
+use crate::zoom::Camera;
+use crate::{
+    Arc, ArrowHead, ArrowStyle, Bezier, Circle, Color, Curve, Ellipse, FillRule, Font, Hairline,
+    Image, Line, LineCap, LineJoin, Marker, MeshVertex, Polygon, Polyline, Rectangle, Sprite,
+    Symbol, Text,
+};
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64(data: &[u8], out: &mut String) {
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+struct SvgWriter {
+    projection: Projection,
+    out: String,
+}
+
+impl SvgWriter {
+    fn paint(&mut self, attribute: &str, color: Color) {
+        let (hex, opacity) = hex_color(color);
+        write!(self.out, " {}=\"{}\"", attribute, hex).unwrap();
+        if opacity < 1. {
+            write!(self.out, " {}-opacity=\"{}\"", attribute, opacity).unwrap();
+        }
+    }
+
+    fn dash(&mut self, dash: Option<&DashPattern>) {
+        if let Some(dash) = dash {
+            let (dashes, offset) = self.projection.dash(dash);
+            let dashes: Vec<String> = dashes.iter().map(|d| d.to_string()).collect();
+            write!(self.out, " stroke-dasharray=\"{}\"", dashes.join(" ")).unwrap();
+            if offset != 0. {
+                write!(self.out, " stroke-dashoffset=\"{}\"", offset).unwrap();
+            }
+        }
+    }
+
+    fn line(
+        &mut self,
+        start: [f32; 2],
+        end: [f32; 2],
+        width: f32,
+        cap: LineCap,
+        dash: Option<&DashPattern>,
+        color: Color,
+    ) {
+        write!(
+            self.out,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke-width=\"{}\"",
+            start[0], start[1], end[0], end[1], width
+        )
+        .unwrap();
+        match cap {
+            LineCap::Butt => {}
+            LineCap::Round => self.out.push_str(" stroke-linecap=\"round\""),
+            LineCap::Square => self.out.push_str(" stroke-linecap=\"square\""),
+        }
+        self.dash(dash);
+        self.paint("stroke", color);
+        self.out.push_str("/>\n");
+    }
+
+    /// Starts a `<rect>` element, rotated by the scene angle `rotation`
+    /// about `center`.
+    fn rect(
+        &mut self,
+        origin: [f32; 2],
+        size: [f32; 2],
+        radius: f32,
+        rotation: f32,
+        center: [f32; 2],
+    ) {
+        write!(
+            self.out,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+            origin[0], origin[1], size[0], size[1]
+        )
+        .unwrap();
+        if radius > 0. {
+            write!(self.out, " rx=\"{}\"", radius).unwrap();
+        }
+        if rotation != 0. {
+            write!(
+                self.out,
+                " transform=\"rotate({} {} {})\"",
+                -rotation.to_degrees(),
+                center[0],
+                center[1]
+            )
+            .unwrap();
+        }
+    }
+}
+
+impl ShapeVisitor for SvgWriter {
+    fn circles(&mut self, data: &[Circle], units: WidthUnits) {
+        for circle in data {
+            let [cx, cy] = self.projection.point(circle.position);
+            let radius = self.projection.length(circle.radius);
+            let stroke_width = if circle.stroke_width > 0. {
+                self.projection.width(circle.stroke_width, units)
+            } else {
+                0.
+            };
+
+            // The stroke is centred on the edge. As on the GPU, the fill
+            // stops at the stroke's inner edge so it doesn't show through a
+            // translucent stroke.
+            if radius > stroke_width {
+                write!(
+                    self.out,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"",
+                    cx,
+                    cy,
+                    radius - stroke_width
+                )
+                .unwrap();
+                self.paint("fill", circle.color);
+                self.out.push_str("/>\n");
+            }
+            if stroke_width > 0. {
+                write!(
+                    self.out,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke-width=\"{}\"",
+                    cx,
+                    cy,
+                    radius,
+                    2. * stroke_width
+                )
+                .unwrap();
+                self.paint("stroke", circle.stroke_color);
+                self.out.push_str("/>\n");
+            }
+        }
+    }
+
+    fn arcs(&mut self, data: &[Arc], units: WidthUnits) {
+        for arc in data {
+            let (start, end) = arc.angles();
+            let middle = (start + end) / 2.;
+            // Scene angles are counter-clockwise with Y up, which is SVG's
+            // negative direction. Drawing the arc as two halves keeps each
+            // under half a turn, so the large arc flag is never needed and
+            // full circles still have distinct endpoints.
+            let sweep = if end < start { 1 } else { 0 };
+            let r = self.projection.length(arc.radius);
+            let [x0, y0] = self.projection.point(arc.point(start));
+            let [x1, y1] = self.projection.point(arc.point(middle));
+            let [x2, y2] = self.projection.point(arc.point(end));
+
+            self.out.push_str("<path d=\"");
+            if arc.stroke_width > 0. {
+                write!(self.out, "M{},{}", x0, y0).unwrap();
+            } else {
+                let [cx, cy] = self.projection.point(arc.center);
+                write!(self.out, "M{},{} L{},{}", cx, cy, x0, y0).unwrap();
+            }
+            write!(
+                self.out,
+                " A{0},{0} 0 0 {1} {2},{3} A{0},{0} 0 0 {1} {4},{5}",
+                r, sweep, x1, y1, x2, y2
+            )
+            .unwrap();
+
+            if arc.stroke_width > 0. {
+                write!(
+                    self.out,
+                    "\" fill=\"none\" stroke-width=\"{}\"",
+                    self.projection.width(2. * arc.stroke_width, units)
+                )
+                .unwrap();
+                self.paint("stroke", arc.color);
+            } else {
+                self.out.push_str(" Z\"");
+                self.paint("fill", arc.color);
+            }
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn ellipses(&mut self, data: &[Ellipse]) {
+        for ellipse in data {
+            let [cx, cy] = self.projection.point(ellipse.position);
+            // Scene rotations are counter-clockwise with Y up, so they are
+            // clockwise in SVG's Y-down coordinates.
+            write!(
+                self.out,
+                "<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" transform=\"rotate({} {} {})\"",
+                cx,
+                cy,
+                self.projection.length(ellipse.radii[0]),
+                self.projection.length(ellipse.radii[1]),
+                -ellipse.rotation.to_degrees(),
+                cx,
+                cy
+            )
+            .unwrap();
+            self.paint("fill", ellipse.color);
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn rectangles(&mut self, data: &[Rectangle], units: WidthUnits) {
+        for rectangle in data {
+            let [x1, y1] = self.projection.point(rectangle.upper_left);
+            let [x2, y2] = self.projection.point(rectangle.bottom_right);
+            let origin = [x1.min(x2), y1.min(y2)];
+            let size = [(x2 - x1).abs(), (y2 - y1).abs()];
+            let center = [(x1 + x2) / 2., (y1 + y2) / 2.];
+            let radius = self
+                .projection
+                .length(rectangle.corner_radius)
+                .min(size[0].min(size[1]) / 2.);
+            let stroke_width = if rectangle.stroke_width > 0. {
+                self.projection.width(rectangle.stroke_width, units)
+            } else {
+                0.
+            };
+
+            // Like circles, the fill is inset to the stroke's inner edge.
+            if size[0].min(size[1]) > 2. * stroke_width {
+                self.rect(
+                    [origin[0] + stroke_width, origin[1] + stroke_width],
+                    [size[0] - 2. * stroke_width, size[1] - 2. * stroke_width],
+                    (radius - stroke_width).max(0.),
+                    rectangle.rotation,
+                    center,
+                );
+                self.paint("fill", rectangle.color);
+                self.out.push_str("/>\n");
+            }
+            if stroke_width > 0. {
+                self.rect(origin, size, radius, rectangle.rotation, center);
+                write!(
+                    self.out,
+                    " fill=\"none\" stroke-width=\"{}\"",
+                    2. * stroke_width
+                )
+                .unwrap();
+                self.paint("stroke", rectangle.stroke_color);
+                self.out.push_str("/>\n");
+            }
+        }
+    }
+
+    fn lines(
+        &mut self,
+        data: &[Line],
+        cap: LineCap,
+        dash: Option<&DashPattern>,
+        units: WidthUnits,
+    ) {
+        for line in data {
+            // The shader extrudes `width` to each side of the line.
+            let width = self.projection.width(2. * line.width, units);
+            let start = self.projection.point(line.start);
+            let end = self.projection.point(line.end);
+            self.line(start, end, width, cap, dash, line.color);
+        }
+    }
+
+    fn arrows(&mut self, data: &[Line], style: &ArrowStyle, units: WidthUnits) {
+        let scale = self.projection.width(1., units);
+        for line in data {
+            let start = self.projection.point(line.start);
+            let end = self.projection.point(line.end);
+
+            if style.head != ArrowHead::Open {
+                self.out.push_str("<path d=\"");
+                for (i, [x, y]) in style
+                    .outline(start, end, line.width, scale)
+                    .iter()
+                    .enumerate()
+                {
+                    let command = if i == 0 { "M" } else { "L" };
+                    write!(self.out, "{}{},{} ", command, x, y).unwrap();
+                }
+                self.out.push_str("Z\"");
+                self.paint("fill", line.color);
+                self.out.push_str("/>\n");
+                continue;
+            }
+
+            let width = 2. * line.width * scale;
+            self.line(start, end, width, LineCap::Butt, None, line.color);
+            for barb in style.barbs(start, end, scale) {
+                let points: Vec<String> =
+                    barb.iter().map(|[x, y]| format!("{},{}", x, y)).collect();
+                write!(
+                    self.out,
+                    "<polyline points=\"{}\" fill=\"none\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"",
+                    points.join(" "),
+                    width
+                )
+                .unwrap();
+                self.paint("stroke", line.color);
+                self.out.push_str("/>\n");
+            }
+        }
+    }
+
+    fn hairlines(&mut self, data: &[Hairline], units: WidthUnits) {
+        for hairline in data {
+            let (start, end, width) = self.projection.hairline(hairline, units);
+            self.line(start, end, width, LineCap::Butt, None, hairline.color);
+        }
+    }
+
+    fn polygons(&mut self, data: &[Polygon]) {
+        for polygon in data {
+            self.out.push_str("<path d=\"");
+            for ring in &polygon.rings {
+                for (i, &point) in ring.iter().enumerate() {
+                    let [x, y] = self.projection.point(point);
+                    let command = if i == 0 { "M" } else { "L" };
+                    write!(self.out, "{}{},{} ", command, x, y).unwrap();
+                }
+                self.out.push_str("Z ");
+            }
+            let rule = match polygon.fill_rule {
+                FillRule::NonZero => "nonzero",
+                FillRule::EvenOdd => "evenodd",
+            };
+            write!(self.out, "\" fill-rule=\"{}\"", rule).unwrap();
+            self.paint("fill", polygon.color);
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn polylines(&mut self, data: &[Polyline], dash: Option<&DashPattern>) {
+        for polyline in data {
+            self.out.push_str("<polyline points=\"");
+            for (i, &point) in polyline.points.iter().enumerate() {
+                let [x, y] = self.projection.point(point);
+                let separator = if i == 0 { "" } else { " " };
+                write!(self.out, "{}{},{}", separator, x, y).unwrap();
+            }
+            write!(
+                self.out,
+                "\" fill=\"none\" stroke-width=\"{}\"",
+                self.projection.length(2. * polyline.width)
+            )
+            .unwrap();
+            match polyline.join {
+                LineJoin::Miter { limit } => {
+                    write!(self.out, " stroke-miterlimit=\"{}\"", limit.max(1.)).unwrap()
+                }
+                LineJoin::Round => self.out.push_str(" stroke-linejoin=\"round\""),
+                LineJoin::Bevel => self.out.push_str(" stroke-linejoin=\"bevel\""),
+            }
+            self.dash(dash);
+            self.paint("stroke", polyline.color);
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn curves(&mut self, data: &[Curve], dash: Option<&DashPattern>) {
+        for curve in data {
+            let (command, points): (&str, &[[f32; 2]]) = match &curve.bezier {
+                Bezier::Quadratic(points) => ("Q", &points[..]),
+                Bezier::Cubic(points) => ("C", &points[..]),
+            };
+            let [x, y] = self.projection.point(points[0]);
+            write!(self.out, "<path d=\"M{},{} {}", x, y, command).unwrap();
+            for &point in &points[1..] {
+                let [x, y] = self.projection.point(point);
+                write!(self.out, " {},{}", x, y).unwrap();
+            }
+            write!(
+                self.out,
+                "\" fill=\"none\" stroke-width=\"{}\" stroke-linejoin=\"round\"",
+                self.projection.length(2. * curve.width)
+            )
+            .unwrap();
+            self.dash(dash);
+            self.paint("stroke", curve.color);
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn texts(&mut self, data: &[Text], font: &Font) {
+        // The viewer falls back to its default sans-serif font if it doesn't
+        // have the family installed.
+        let family = match font.family() {
+            Some(name) => {
+                let mut family = String::from("'");
+                for c in name.chars() {
+                    match c {
+                        '&' => family.push_str("&amp;"),
+                        '<' => family.push_str("&lt;"),
+                        '"' => family.push_str("&quot;"),
+                        '\'' | '\\' => {
+                            family.push('\\');
+                            family.push(c);
+                        }
+                        c => family.push(c),
+                    }
+                }
+                family.push_str("', sans-serif");
+                family
+            }
+            None => "sans-serif".to_string(),
+        };
+
+        for text in data {
+            let anchor = match text.anchor.fractions().0 {
+                f if f < 0.25 => "start",
+                f if f < 0.75 => "middle",
+                _ => "end",
+            };
+            let font_size = self.projection.length(font.em_size(text.size));
+
+            for line in text.lines(font) {
+                let [x, y] = self.projection.point(line.origin);
+                write!(
+                    self.out,
+                    "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\" text-anchor=\"{}\"",
+                    x, y, family, font_size, anchor
+                )
+                .unwrap();
+                self.paint("fill", text.color);
+                self.out.push('>');
+                for c in line.text.chars() {
+                    match c {
+                        '&' => self.out.push_str("&amp;"),
+                        '<' => self.out.push_str("&lt;"),
+                        '>' => self.out.push_str("&gt;"),
+                        c => self.out.push(c),
+                    }
+                }
+                self.out.push_str("</text>\n");
+            }
+        }
+    }
+
+    fn images(&mut self, images: &[Image], data: &[Sprite]) {
+        let mut uris: Vec<Option<String>> = vec![None; images.len()];
+        for sprite in data {
+            let uri = uris[sprite.image as usize].get_or_insert_with(|| {
+                let mut png = Vec::new();
+                images[sprite.image as usize].write_png(&mut png).unwrap();
+                let mut uri = String::from("data:image/png;base64,");
+                base64(&png, &mut uri);
+                uri
+            });
+
+            let [cx, cy] = self.projection.point(sprite.position);
+            let width = self.projection.length(sprite.size[0]);
+            let height = self.projection.length(sprite.size[1]);
+            writeln!(
+                self.out,
+                "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" transform=\"rotate({} {} {})\" opacity=\"{}\" preserveAspectRatio=\"none\" href=\"{}\"/>",
+                cx - width / 2.,
+                cy - height / 2.,
+                width,
+                height,
+                -sprite.rotation.to_degrees(),
+                cx,
+                cy,
+                sprite.opacity,
+                uri
+            )
+            .unwrap();
+        }
+    }
+
+    fn markers(&mut self, data: &[Marker]) {
+        for marker in data {
+            // Marker sizes are in pixels, which are output units already.
+            let [cx, cy] = self.projection.point(marker.position);
+            if marker.symbol == Symbol::Circle {
+                write!(
+                    self.out,
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"",
+                    cx,
+                    cy,
+                    marker.size / 2.
+                )
+                .unwrap();
+            } else {
+                self.out.push_str("<path d=\"");
+                for (i, [x, y]) in marker.symbol.outline(marker.size).iter().enumerate() {
+                    let command = if i == 0 { "M" } else { "L" };
+                    write!(self.out, "{}{},{} ", command, cx + x, cy - y).unwrap();
+                }
+                self.out.push_str("Z\"");
+            }
+            self.paint("fill", marker.color);
+            self.out.push_str("/>\n");
+        }
+    }
+
+    fn mesh(&mut self, vertices: &[MeshVertex], indices: &[u32]) {
+        // SVG has no per-vertex colors, so each triangle is filled with the
+        // average of the colors of its corners.
+        for (corners, color) in flat_triangles(vertices, indices) {
+            self.out.push_str("<path d=\"");
+            for (i, &point) in corners.iter().enumerate() {
+                let [x, y] = self.projection.point(point);
+                let command = if i == 0 { "M" } else { "L" };
+                write!(self.out, "{}{},{} ", command, x, y).unwrap();
+            }
+            self.out.push_str("Z\"");
+            self.paint("fill", color);
+            self.out.push_str("/>\n");
+        }
+    }
+}
+
+/// Serializes the shapes of `layer` into an SVG document, as they would
+/// appear in a `width` by `height` frame viewed through `camera`.
+pub fn to_svg<T: Layer>(layer: &T, width: u32, height: u32, camera: Camera) -> String {
+    let size = PhysicalSize::new(width, height);
+    let mut writer = SvgWriter {
+        projection: Projection::new(width as f32, height as f32, camera),
+        out: String::new(),
+    };
+
+    writeln!(
+        writer.out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )
+    .unwrap();
+    layer.visit_shapes(size, &mut writer);
+    writer.out.push_str("</svg>\n");
+
+    writer.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CirclesLayer, EllipsesLayer, GenericLayer, GroupLayer, ImagesLayer, LinesLayer,
+        RectanglesLayer,
+    };
+
+    const RED: Color = Color(u32::from_ne_bytes([0xff, 0, 0, 0xff]));
+    const HALF_BLUE: Color = Color(u32::from_ne_bytes([0, 0, 0xff, 0x80]));
+
+    /// Exports `layers` in a 200 by 100 frame, where the default camera maps
+    /// the scene origin to (100, 50) and two scene units to one output unit.
+    fn export(layers: Vec<Box<dyn GenericLayer>>) -> Vec<String> {
+        let svg = to_svg(&GroupLayer::new(layers), 200, 100, Camera::default());
+        let lines: Vec<String> = svg.lines().map(str::to_string).collect();
+        assert!(lines[0].starts_with("<svg "));
+        assert_eq!(lines.last().unwrap(), "</svg>");
+        lines[1..lines.len() - 1].to_vec()
+    }
+
+    #[test]
+    fn base64_padding() {
+        for (data, expected) in [
+            (&b"Man"[..], "TWFu"),
+            (b"Ma", "TWE="),
+            (b"M", "TQ=="),
+            (b"", ""),
+        ] {
+            let mut out = String::new();
+            base64(data, &mut out);
+            assert_eq!(out, expected);
+        }
+    }
+
+    #[test]
+    fn layers_in_painters_order() {
+        let circle = Circle {
+            position: [20., 40.],
+            color: RED,
+            radius: 10.,
+            ..Default::default()
+        };
+        let line = Line {
+            start: [-200., 0.],
+            end: [200., 0.],
+            color: HALF_BLUE,
+            width: 2.,
+        };
+        let shapes = export(vec![
+            Box::new(LinesLayer::new(vec![line])),
+            Box::new(CirclesLayer::new(vec![circle])),
+        ]);
+
+        // Scene Y points up, so the circle above the origin ends up above
+        // the middle of the frame.
+        assert_eq!(
+            shapes,
+            [
+                "<line x1=\"0\" y1=\"50\" x2=\"200\" y2=\"50\" stroke-width=\"2\" stroke=\"#0000ff\" stroke-opacity=\"0.5019608\"/>",
+                "<circle cx=\"110\" cy=\"30\" r=\"5\" fill=\"#ff0000\"/>",
+            ]
+        );
+    }
+
+    #[test]
+    fn fill_inside_stroke() {
+        let circle = Circle {
+            color: RED,
+            radius: 20.,
+            stroke_width: 4.,
+            stroke_color: HALF_BLUE,
+            ..Default::default()
+        };
+        let rectangle = Rectangle {
+            upper_left: [-40., 20.],
+            bottom_right: [40., -20.],
+            color: RED,
+            corner_radius: 8.,
+            stroke_width: 2.,
+            stroke_color: HALF_BLUE,
+            ..Default::default()
+        };
+        let shapes = export(vec![
+            Box::new(CirclesLayer::new(vec![circle])),
+            Box::new(RectanglesLayer::new(vec![rectangle])),
+        ]);
+
+        assert_eq!(
+            shapes,
+            [
+                "<circle cx=\"100\" cy=\"50\" r=\"8\" fill=\"#ff0000\"/>",
+                "<circle cx=\"100\" cy=\"50\" r=\"10\" fill=\"none\" stroke-width=\"4\" stroke=\"#0000ff\" stroke-opacity=\"0.5019608\"/>",
+                "<rect x=\"81\" y=\"41\" width=\"38\" height=\"18\" rx=\"3\" fill=\"#ff0000\"/>",
+                "<rect x=\"80\" y=\"40\" width=\"40\" height=\"20\" rx=\"4\" fill=\"none\" stroke-width=\"2\" stroke=\"#0000ff\" stroke-opacity=\"0.5019608\"/>",
+            ]
+        );
+    }
+
+    #[test]
+    fn stroke_wider_than_shape_has_no_fill() {
+        let circle = Circle {
+            color: RED,
+            radius: 2.,
+            stroke_width: 4.,
+            stroke_color: HALF_BLUE,
+            ..Default::default()
+        };
+        let shapes = export(vec![Box::new(CirclesLayer::new(vec![circle]))]);
+
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes[0].contains("fill=\"none\""));
+    }
+
+    #[test]
+    fn rotation_is_clockwise_in_svg() {
+        let ellipse = Ellipse {
+            position: [0., 0.],
+            radii: [20., 10.],
+            rotation: std::f32::consts::FRAC_PI_2,
+            color: RED,
+        };
+        let shapes = export(vec![Box::new(EllipsesLayer::new(vec![ellipse]))]);
+
+        assert_eq!(
+            shapes,
+            ["<ellipse cx=\"100\" cy=\"50\" rx=\"10\" ry=\"5\" transform=\"rotate(-90 100 50)\" fill=\"#ff0000\"/>"]
+        );
+    }
+
+    #[test]
+    fn images_embedded_as_png() {
+        let image = Image {
+            width: 1,
+            height: 1,
+            data: vec![0xff, 0, 0, 0xff],
+        };
+        let sprite = Sprite {
+            position: [0., 0.],
+            size: [20., 20.],
+            rotation: 0.,
+            opacity: 1.,
+            image: 0,
+        };
+        let layer = ImagesLayer::new(vec![image.clone()], vec![sprite, sprite]).unwrap();
+        let shapes = export(vec![Box::new(layer)]);
+
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+        let mut uri = String::from("data:image/png;base64,");
+        base64(&png, &mut uri);
+        assert!(uri.starts_with("data:image/png;base64,iVBORw0KGgo"));
+
+        assert_eq!(shapes.len(), 2);
+        for shape in shapes {
+            assert!(shape.starts_with("<image x=\"95\" y=\"45\" width=\"10\" height=\"10\" "));
+            assert!(shape.ends_with(&format!(" href=\"{}\"/>", uri)));
+        }
+    }
+}
@@ -4,7 +4,7 @@ use std::mem::size_of;
 use wgpu::BufferDescriptor;
 use wgpu::util::BufferInitDescriptor;
 use wgpu::util::DeviceExt;
-use wgpu::{Buffer, BufferSlice, CommandEncoder, Device, VertexBufferLayout};
+use wgpu::{Buffer, BufferSlice, BufferUsage, CommandEncoder, Device, VertexBufferLayout};
 
 pub trait GpuSerializable: Sized {
     fn gpu_serialize(data: &[Self]) -> &[u8];
@@ -12,10 +12,33 @@ pub trait GpuSerializable: Sized {
     fn buffer_layout<'a>() -> VertexBufferLayout<'a>;
 }
 
+/// Indices into a vertex buffer, in `GpuBuffer`s created with
+/// `BufferUsage::INDEX`.
+impl GpuSerializable for u32 {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: size_of::<u32>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Uint32,
+                },
+            ],
+        }
+    }
+}
+
 pub struct GpuBuffer<T: GpuSerializable> {
     buffer: Buffer,
     num_items: u32,
     capacity: u32,
+    usage: BufferUsage,
     _phantom: PhantomData<T>,
 }
 
@@ -24,35 +47,43 @@ impl<T: GpuSerializable> GpuBuffer<T> {
         self.num_items
     }
 
-    pub fn all(&self) -> BufferSlice {
+    pub fn all(&self) -> BufferSlice<'_> {
         self.buffer.slice(..)
     }
 
-    fn create_buffer(capacity: u32, device: &Device) -> Buffer {
+    fn create_buffer(capacity: u32, usage: BufferUsage, device: &Device) -> Buffer {
         device.create_buffer(&BufferDescriptor {
             label: None,
             size: size_of::<T>() as u64 * capacity as u64,
-            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
+            usage: usage | wgpu::BufferUsage::COPY_DST,
             mapped_at_creation: false,
         })
     }
 
     pub fn new_with_capacity(capacity: u32, device: &Device) -> Self {
-        let buffer = Self::create_buffer(capacity, device);
+        let usage = wgpu::BufferUsage::VERTEX;
+        let buffer = Self::create_buffer(capacity, usage, device);
 
         GpuBuffer {
             buffer,
             num_items: 0,
             capacity,
-            _phantom: PhantomData::default(),
+            usage,
+            _phantom: PhantomData,
         }
     }
 
     pub fn new(data: &[T], device: &Device) -> Self {
+        Self::new_with_usage(data, wgpu::BufferUsage::VERTEX, device)
+    }
+
+    /// Like `new`, for buffers used other than as vertex buffers, such as
+    /// `BufferUsage::INDEX`.
+    pub fn new_with_usage(data: &[T], usage: BufferUsage, device: &Device) -> Self {
         let buffer = device.create_buffer_init(&BufferInitDescriptor {
             contents: T::gpu_serialize(data),
             label: None,
-            usage: wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST
+            usage: usage | wgpu::BufferUsage::COPY_DST
         });
 
         let capacity = data.len() as u32;
@@ -61,14 +92,15 @@ impl<T: GpuSerializable> GpuBuffer<T> {
             buffer,
             capacity,
             num_items: data.len() as u32,
-            _phantom: PhantomData::default(),
+            usage,
+            _phantom: PhantomData,
         }
     }
 
     pub fn update(&mut self, data: &[T], device: &Device, encoder: RefMut<CommandEncoder>) {
         if data.len() as u32 > self.capacity {
             let capacity = data.len() as u32;
-            let buffer = Self::create_buffer(capacity, device);
+            let buffer = Self::create_buffer(capacity, self.usage, device);
 
             self.capacity = capacity;
             self.buffer = buffer;
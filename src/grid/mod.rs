@@ -4,7 +4,13 @@ use crate::{
 };
 
 use crate::color::Color;
+use crate::export::ShapeVisitor;
+use crate::units::WidthUnits;
 use wgpu::SwapChainDescriptor;
+use winit::dpi::PhysicalSize;
+
+/// Half the width of the grid lines, in pixels.
+const LINE_WIDTH: f32 = 1.;
 
 pub struct GridLayer {
     rows: u32,
@@ -28,46 +34,59 @@ impl Default for GridLayer {
     }
 }
 
-impl Layer for GridLayer {
-    type D = HairlinesLayerDrawable;
-
-    fn init_drawable(&self, draw_context: &DrawContext) -> HairlinesLayerDrawable {
-        let DrawContext {
-            device: _,
-            sc_desc,
-            transform_layout: _,
-        } = *draw_context;
-        let SwapChainDescriptor { height, width, .. } = sc_desc;
+impl GridLayer {
+    fn hairlines(&self, size: PhysicalSize<u32>) -> Vec<Hairline> {
+        let PhysicalSize { width, height } = size;
 
-        let offset_x = -(*width as f32) + (*width as f32 * 2. / self.cols as f32);
-        let offset_y = -(*height as f32) + (*height as f32 * 2. / self.rows as f32);
+        let offset_x = -(width as f32) + (width as f32 * 2. / self.cols as f32);
+        let offset_y = -(height as f32) + (height as f32 * 2. / self.rows as f32);
         let mut grid = (0..self.rows)
             .map(|r| Hairline {
                 orientation: Orientation::Horizontal,
-                width: 0.004,
+                width: LINE_WIDTH,
                 color: self.color,
-                location: offset_y + (r as f32) * (2. * *height as f32 / (self.rows + 1) as f32),
+                location: offset_y + (r as f32) * (2. * height as f32 / (self.rows + 1) as f32),
             })
             .collect::<Vec<_>>();
         let cols = (0..self.cols)
             .map(|c| Hairline {
                 orientation: Orientation::Vertical,
-                width: 0.004,
+                width: LINE_WIDTH,
                 color: self.color,
-                location: offset_x + (c as f32) * (2. * *width as f32 / (self.cols + 1) as f32),
+                location: offset_x + (c as f32) * (2. * width as f32 / (self.cols + 1) as f32),
             })
             .collect::<Vec<_>>();
 
         grid.extend(cols);
+        grid
+    }
+}
 
-        let lines = HairlinesLayer::new(grid);
+impl Layer for GridLayer {
+    type D = HairlinesLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> HairlinesLayerDrawable {
+        let SwapChainDescriptor { height, width, .. } = *draw_context.sc_desc;
+
+        let lines = HairlinesLayer::new(
+            self.hairlines(PhysicalSize::new(width, height)),
+            WidthUnits::Pixels,
+        );
 
         lines.init_drawable(draw_context)
     }
+
+    fn visit_shapes(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.hairlines(&self.hairlines(size), WidthUnits::Pixels);
+    }
 }
 
 impl GenericLayer for GridLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
         GenericDrawable::new(self.init_drawable(draw_context))
     }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
 }
@@ -2,12 +2,15 @@ use crate::layer::{DrawContext, DrawState, Drawable, Layer};
 use crate::{GenericDrawable, GenericLayer};
 
 use crate::color::Color;
+use crate::export::ShapeVisitor;
 use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::units::WidthUnits;
 use std::borrow::Cow;
 use wgpu::{
     BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
     VertexBufferLayout,
 };
+use winit::dpi::PhysicalSize;
 
 #[repr(u32)]
 #[derive(Copy, Clone, Debug)]
@@ -24,6 +27,8 @@ unsafe impl bytemuck::Pod for Orientation {}
 pub struct Hairline {
     pub location: f32,
     pub color: Color,
+    /// Distance the line extends to each side of `location`, in the units
+    /// the layer was created with.
     pub width: f32,
     pub orientation: Orientation,
 }
@@ -65,11 +70,16 @@ impl GpuSerializable for Hairline {
 
 pub struct HairlinesLayer {
     data: Vec<Hairline>,
+    units: WidthUnits,
 }
 
 impl HairlinesLayer {
-    pub fn new(data: Vec<Hairline>) -> Self {
-        HairlinesLayer { data }
+    /// Creates a layer whose hairline widths are in `units`. Widths used to
+    /// be in clip space, which `WidthUnits` has no equivalent for; half the
+    /// old width times the window size in pixels gives the same line with
+    /// `WidthUnits::Pixels`.
+    pub fn new(data: Vec<Hairline>, units: WidthUnits) -> Self {
+        HairlinesLayer { data, units }
     }
 }
 
@@ -79,7 +89,7 @@ pub struct HairlinesLayerDrawable {
 }
 
 impl Drawable for HairlinesLayerDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         let mut render_pass = draw_state.render_pass.borrow_mut();
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, draw_state.bind_group, &[]);
@@ -96,8 +106,10 @@ impl Layer for HairlinesLayer {
             device,
             sc_desc,
             transform_layout,
+            sample_count,
+            ..
         } = *draw_context;
-        let instance_buffer = GpuBuffer::new(&self.data, &device);
+        let instance_buffer = GpuBuffer::new(&self.data, device);
 
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
@@ -108,7 +120,9 @@ impl Layer for HairlinesLayer {
 
         let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
             label: None,
-            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            source: ShaderSource::Wgsl(Cow::Owned(
+                self.units.shader_source(include_str!("shader.wgsl")),
+            )),
             flags: Default::default(),
         });
 
@@ -145,7 +159,7 @@ impl Layer for HairlinesLayer {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -156,10 +170,18 @@ impl Layer for HairlinesLayer {
             instance_buffer,
         }
     }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.hairlines(&self.data, self.units);
+    }
 }
 
 impl GenericLayer for HairlinesLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
         GenericDrawable::new(self.init_drawable(draw_context))
     }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
 }
@@ -4,20 +4,10 @@ struct VertexOutput {
     [[builtin(position)]] position: vec4<f32>;
 };
 
-[[block]]
-struct Uniforms {
-    transform: mat4x4<f32>;
-};
-
-[[group(0), binding(0)]]
-var uniforms: Uniforms;
-
-[[stage(vertex)]]
-fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
-    [[location(0)]] position: f32,
-    [[location(1)]] color: vec4<f32>,
-    [[location(2)]] width: f32,
-    [[location(3)]] vertical: u32,
+// `width` is in clip-space units: its x is used for vertical hairlines and
+// its y for horizontal ones.
+fn vertex(in_vertex_index: u32, position: f32, color: vec4<f32>, width: vec2<f32>,
+    vertical: u32,
 ) -> VertexOutput {
     var out: VertexOutput;
 
@@ -29,15 +19,15 @@ fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
     let scaled: vec4<f32> = uniforms.transform * vec4<f32>(position, position, 0.0, 1.0);
 
     if (vertical == 0u) {
-        c1 = vec2<f32>(scaled.x - width, -1.0);
-        c2 = vec2<f32>(scaled.x + width, -1.0);
-        c3 = vec2<f32>(scaled.x - width, 1.0);
-        c4 = vec2<f32>(scaled.x + width, 1.0);
+        c1 = vec2<f32>(scaled.x - width.x, -1.0);
+        c2 = vec2<f32>(scaled.x + width.x, -1.0);
+        c3 = vec2<f32>(scaled.x - width.x, 1.0);
+        c4 = vec2<f32>(scaled.x + width.x, 1.0);
     } else {
-        c1 = vec2<f32>(-1.0, scaled.y - width);
-        c2 = vec2<f32>(-1.0, scaled.y + width);
-        c3 = vec2<f32>(1.0, scaled.y - width);
-        c4 = vec2<f32>(1.0, scaled.y + width);
+        c1 = vec2<f32>(-1.0, scaled.y - width.y);
+        c2 = vec2<f32>(-1.0, scaled.y + width.y);
+        c3 = vec2<f32>(1.0, scaled.y - width.y);
+        c4 = vec2<f32>(1.0, scaled.y + width.y);
     }
 
     switch (i32(in_vertex_index)) {
@@ -69,6 +59,19 @@ fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
     return out;
 }
 
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] position: f32,
+    [[location(1)]] color: vec4<f32>,
+    [[location(2)]] width: f32,
+    [[location(3)]] vertical: u32,
+) -> VertexOutput {
+    // Clip-space units per scene unit, horizontally and vertically.
+    let scale: vec4<f32> = uniforms.transform * vec4<f32>(1., 1., 0., 0.);
+    return vertex(in_vertex_index, position, color, width * width_scale() * scale.xy,
+        vertical);
+}
+
 [[stage(fragment)]]
 fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
     let dx: f32 = fwidth(in.edge.x);
@@ -0,0 +1,294 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::iter;
+use std::num::NonZeroU32;
+use std::path::Path;
+use std::time::Duration;
+
+use futures::executor::block_on;
+use wgpu::{CommandEncoder, Device, Queue, Texture, TextureFormat, TextureView};
+use winit::dpi::PhysicalSize;
+
+use crate::error::Error;
+use crate::layer::{Drawable, Layer};
+use crate::render::{encode_frame, request_device, FrameState, Multisample, Renderer, CLEAR_COLOR};
+use crate::zoom::{Camera, Mat4};
+
+pub(crate) const BYTES_PER_PIXEL: u32 = 4;
+
+/// An 8-bit RGBA image, stored row by row from the top of the frame.
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * self.width + x) * BYTES_PER_PIXEL) as usize;
+        [
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ]
+    }
+
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = BufReader::new(File::open(path)?);
+
+        let (info, mut reader) = png::Decoder::new(file).read_info()?;
+        if info.color_type != png::ColorType::RGBA || info.bit_depth != png::BitDepth::Eight {
+            let e = png::DecodingError::Other("expected an 8-bit RGBA image".into());
+            return Err(e.into());
+        }
+
+        let mut data = vec![0; info.buffer_size()];
+        reader.next_frame(&mut data)?;
+
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            data,
+        })
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn write_png(&self, out: impl Write) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(out, self.width, self.height);
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.data)?;
+
+        Ok(())
+    }
+}
+
+pub fn offscreen_texture(
+    device: &Device,
+    size: PhysicalSize<u32>,
+    format: TextureFormat,
+) -> Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen texture"),
+        size: wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsage::RENDER_ATTACHMENT | wgpu::TextureUsage::COPY_SRC,
+    })
+}
+
+/// Copies `texture` back to the CPU after the commands in `encoder` have run.
+pub fn read_texture(
+    device: &Device,
+    queue: &Queue,
+    mut encoder: CommandEncoder,
+    texture: &Texture,
+    size: PhysicalSize<u32>,
+    format: TextureFormat,
+) -> Result<Image, Error> {
+    let bgra = match format {
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+        _ => return Err(Error::UnsupportedFormat(format)),
+    };
+
+    let unpadded_bytes_per_row = size.width * BYTES_PER_PIXEL;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Readback buffer"),
+        size: padded_bytes_per_row as u64 * size.height as u64,
+        usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    encoder.copy_texture_to_buffer(
+        wgpu::ImageCopyTexture {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+        },
+        wgpu::ImageCopyBuffer {
+            buffer: &buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_bytes_per_row),
+                rows_per_image: NonZeroU32::new(size.height),
+            },
+        },
+        wgpu::Extent3d {
+            width: size.width,
+            height: size.height,
+            depth_or_array_layers: 1,
+        },
+    );
+
+    queue.submit(iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let mapping = slice.map_async(wgpu::MapMode::Read);
+    device.poll(wgpu::Maintain::Wait);
+    block_on(mapping).map_err(|_| Error::ReadBack)?;
+
+    let mut data = Vec::with_capacity((unpadded_bytes_per_row * size.height) as usize);
+    {
+        let padded = slice.get_mapped_range();
+        for row in padded.chunks(padded_bytes_per_row as usize) {
+            data.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+        }
+    }
+    buffer.unmap();
+
+    if bgra {
+        for pixel in data.chunks_mut(BYTES_PER_PIXEL as usize) {
+            pixel.swap(0, 2);
+        }
+    }
+
+    Ok(Image {
+        width: size.width,
+        height: size.height,
+        data,
+    })
+}
+
+/// A layer's drawable bound to an offscreen texture, for rendering frames
+/// without a window or surface.
+pub struct Headless<D: Drawable> {
+    device: Device,
+    queue: Queue,
+    renderer: Renderer,
+    matrix: Mat4,
+    texture: Texture,
+    view: TextureView,
+    multisample: Multisample,
+    size: PhysicalSize<u32>,
+    format: TextureFormat,
+    drawable: D,
+}
+
+impl<D: Drawable> Headless<D> {
+    pub fn new<T: Layer<D = D>>(
+        layer: &T,
+        size: PhysicalSize<u32>,
+        camera: Camera,
+        sample_count: u32,
+    ) -> Result<Self, Error> {
+        // Devices are requested with the default limits.
+        let max_size = wgpu::Limits::default().max_texture_dimension_2d;
+        if size.width == 0 || size.height == 0 || size.width.max(size.height) > max_size {
+            return Err(Error::ImageSize {
+                width: size.width,
+                height: size.height,
+            });
+        }
+
+        let format = TextureFormat::Rgba8UnormSrgb;
+
+        let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
+        let (device, queue) = block_on(request_device(
+            &instance,
+            None,
+            wgpu::PowerPreference::default(),
+        ))?;
+
+        let renderer = Renderer::new(&device, format, sample_count);
+        let drawable = renderer.init_drawable(&device, &queue, layer, size);
+
+        let texture = offscreen_texture(&device, size, format);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let multisample = Multisample::new(&device, size, format, sample_count);
+
+        Ok(Headless {
+            device,
+            queue,
+            renderer,
+            matrix: camera.matrix(size),
+            texture,
+            view,
+            multisample,
+            size,
+            format,
+            drawable,
+        })
+    }
+
+    /// Updates the drawable as if `elapsed` time has passed since the first
+    /// frame, then renders it.
+    pub fn render(&mut self, elapsed: Duration) -> Result<Image, Error> {
+        let frame = FrameState {
+            transform: self.matrix,
+            size: self.size,
+            elapsed,
+        };
+        let encoder = encode_frame(
+            &self.device,
+            &self.renderer,
+            frame,
+            &mut self.drawable,
+            self.multisample.target(&self.view),
+            CLEAR_COLOR,
+        );
+
+        read_texture(
+            &self.device,
+            &self.queue,
+            encoder,
+            &self.texture,
+            self.size,
+            self.format,
+        )
+    }
+}
+
+/// Renders `layer` once into an offscreen texture and returns its pixels.
+///
+/// No window or surface is created, so this works on machines without a
+/// display as long as some adapter (including a software one) is available.
+/// Fails with `Error::ImageSize` if either side is zero or larger than a
+/// texture can be.
+pub fn render_to_image<T: Layer>(
+    layer: &T,
+    width: u32,
+    height: u32,
+    camera: Camera,
+) -> Result<Image, Error> {
+    Headless::new(layer, PhysicalSize::new(width, height), camera, 1)?.render(Duration::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CirclesLayer;
+
+    #[test]
+    fn empty_image_size() {
+        // The size is checked before looking for an adapter.
+        let layer = CirclesLayer::new(Vec::new());
+        for &(width, height) in &[(0, 10), (10, 0), (0, 0), (1, 100_000)] {
+            match render_to_image(&layer, width, height, Camera::default()) {
+                Err(Error::ImageSize {
+                    width: w,
+                    height: h,
+                }) => {
+                    assert_eq!((w, h), (width, height))
+                }
+                other => panic!("{}x{}: {:?}", width, height, other.map(|_| ())),
+            }
+        }
+    }
+}
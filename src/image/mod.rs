@@ -0,0 +1,307 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::error::Error;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::headless::{Image, BYTES_PER_PIXEL};
+use crate::texture::{texture_layout, TextureBinding};
+use std::borrow::Cow;
+use std::ops::Range;
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    TextureFormat, VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+/// An instance of one of an `ImagesLayer`'s images, drawn as a quad.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct Sprite {
+    /// Center of the quad.
+    pub position: [f32; 2],
+    /// Width and height of the quad, in scene units.
+    pub size: [f32; 2],
+    /// Counter-clockwise rotation about `position`, in radians.
+    pub rotation: f32,
+    pub opacity: f32,
+    /// Index of the image to draw, in the layer's images.
+    pub image: u32,
+}
+
+impl Default for Sprite {
+    fn default() -> Self {
+        Sprite {
+            position: [0., 0.],
+            size: [0., 0.],
+            rotation: 0.,
+            opacity: 1.,
+            image: 0,
+        }
+    }
+}
+
+impl GpuSerializable for Sprite {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Sprite>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ],
+        }
+    }
+}
+
+/// Splits `data` into runs of consecutive sprites that use the same image,
+/// so that they can be drawn in order with one draw call per run.
+fn runs(data: &[Sprite]) -> Vec<(usize, Range<u32>)> {
+    let mut runs: Vec<(usize, Range<u32>)> = Vec::new();
+    for (i, sprite) in data.iter().enumerate() {
+        let image = sprite.image as usize;
+        match runs.last_mut() {
+            Some((last, range)) if *last == image => range.end = i as u32 + 1,
+            _ => runs.push((image, i as u32..i as u32 + 1)),
+        }
+    }
+    runs
+}
+
+/// Fails if a sprite refers to an image past the first `count`.
+fn check_images(data: &[Sprite], count: usize) -> Result<(), Error> {
+    match data.iter().find(|sprite| sprite.image as usize >= count) {
+        Some(sprite) => Err(Error::MissingImage(sprite.image)),
+        None => Ok(()),
+    }
+}
+
+pub struct ImagesLayer {
+    images: Vec<Image>,
+    data: Vec<Sprite>,
+}
+
+impl ImagesLayer {
+    /// Each sprite in `data` draws the image in `images` at its `image`
+    /// index. Fails if a sprite's index is out of range, or if an image
+    /// can't be uploaded as a texture.
+    pub fn new(images: Vec<Image>, data: Vec<Sprite>) -> Result<Self, Error> {
+        // Devices are requested with the default limits.
+        let max_size = wgpu::Limits::default().max_texture_dimension_2d;
+        for image in &images {
+            let (width, height) = (image.width, image.height);
+            if width == 0 || height == 0 || width > max_size || height > max_size {
+                return Err(Error::ImageSize { width, height });
+            }
+            let expected = (width * height * BYTES_PER_PIXEL) as usize;
+            if image.data.len() != expected {
+                return Err(Error::ImageDataLength {
+                    expected,
+                    actual: image.data.len(),
+                });
+            }
+        }
+        check_images(&data, images.len())?;
+
+        Ok(ImagesLayer { images, data })
+    }
+}
+
+pub struct ImagesLayerDrawable {
+    render_pipeline: RenderPipeline,
+    textures: Vec<TextureBinding>,
+    runs: Vec<(usize, Range<u32>)>,
+    instance_buffer: GpuBuffer<Sprite>,
+}
+
+impl ImagesLayerDrawable {
+    /// Replaces the sprites drawn by this drawable, which keeps the images
+    /// of its layer. Fails, leaving the sprites unchanged, if a sprite's
+    /// index is out of range.
+    pub fn set_sprites(
+        &mut self,
+        data: &[Sprite],
+        update_state: &UpdateState,
+    ) -> Result<(), Error> {
+        check_images(data, self.textures.len())?;
+        self.instance_buffer
+            .update(data, update_state.device, update_state.encoder.borrow_mut());
+        self.runs = runs(data);
+        Ok(())
+    }
+}
+
+impl Drawable for ImagesLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.all());
+        for (image, range) in &self.runs {
+            render_pass.set_bind_group(1, &self.textures[*image].bind_group, &[]);
+            render_pass.draw(0..6, range.clone());
+        }
+    }
+}
+
+impl GenericLayer for ImagesLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+impl Layer for ImagesLayer {
+    type D = ImagesLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> ImagesLayerDrawable {
+        let DrawContext {
+            device,
+            queue,
+            sc_desc,
+            transform_layout,
+            sample_count,
+        } = *draw_context;
+        let instance_buffer = GpuBuffer::new(&self.data, device);
+
+        let texture_layout = texture_layout(device);
+        let textures = self
+            .images
+            .iter()
+            .map(|image| {
+                TextureBinding::new(
+                    device,
+                    queue,
+                    &texture_layout,
+                    TextureFormat::Rgba8UnormSrgb,
+                    4,
+                    (image.width, image.height),
+                    &image.data,
+                )
+            })
+            .collect();
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout, &texture_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[Sprite::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        ImagesLayerDrawable {
+            render_pipeline,
+            textures,
+            runs: runs(&self.data),
+            instance_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.images(&self.images, &self.data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sprites(images: &[u32]) -> Vec<Sprite> {
+        images
+            .iter()
+            .map(|&image| Sprite {
+                position: [0., 0.],
+                size: [1., 1.],
+                rotation: 0.,
+                opacity: 1.,
+                image,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn runs_of_same_image() {
+        assert_eq!(
+            runs(&sprites(&[0, 0, 1, 0, 0, 0])),
+            [(0, 0..2), (1, 2..3), (0, 3..6)]
+        );
+        assert!(runs(&[]).is_empty());
+    }
+
+    #[test]
+    fn image_indices_checked() {
+        assert!(check_images(&sprites(&[0, 1, 1]), 2).is_ok());
+        assert!(matches!(
+            check_images(&sprites(&[0, 2, 3]), 2),
+            Err(Error::MissingImage(2))
+        ));
+    }
+}
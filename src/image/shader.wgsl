@@ -0,0 +1,59 @@
+struct VertexOutput {
+    [[location(0)]] uv: vec2<f32>;
+    [[location(1)]] opacity: f32;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+[[group(1), binding(0)]]
+var image: texture_2d<f32>;
+
+[[group(1), binding(1)]]
+var image_sampler: sampler;
+
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(-1., -1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., 1.),
+);
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] size: vec2<f32>,
+    [[location(2)]] rotation: f32,
+    [[location(3)]] opacity: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let corner: vec2<f32> = corners[in_vertex_index];
+    let local: vec2<f32> = corner * size / 2.;
+    let c: f32 = cos(rotation);
+    let s: f32 = sin(rotation);
+    let offset: vec2<f32> = vec2<f32>(c * local.x - s * local.y, s * local.x + c * local.y);
+
+    // Image rows run from the top down, while scene Y increases going up.
+    out.uv = vec2<f32>(corner.x + 1., 1. - corner.y) / 2.;
+    out.opacity = opacity;
+    out.position = uniforms.transform * vec4<f32>(position + offset, 0., 1.);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let color: vec4<f32> = textureSample(image, image_sampler, in.uv);
+    let alpha: f32 = color.a * in.opacity;
+
+    return vec4<f32>(color.rgb * alpha, alpha);
+}
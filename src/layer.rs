@@ -1,36 +1,72 @@
 use std::cell::RefCell;
-use wgpu::{BindGroup, BindGroupLayout, CommandEncoder, Device, RenderPass, SwapChainDescriptor};
+use std::time::Duration;
+use wgpu::{
+    BindGroup, BindGroupLayout, CommandEncoder, Device, Queue, RenderPass, SwapChainDescriptor,
+};
+use winit::dpi::PhysicalSize;
+
+use crate::export::ShapeVisitor;
+use crate::zoom::Mat4;
 
 pub struct DrawContext<'a> {
     pub device: &'a Device,
+    /// For uploading textures and other data when the drawable is created.
+    pub queue: &'a Queue,
     pub sc_desc: &'a SwapChainDescriptor,
     pub transform_layout: &'a BindGroupLayout,
+    /// Number of samples per pixel that render pipelines must be created with.
+    pub sample_count: u32,
 }
 
 pub trait Layer {
     type D: Drawable;
 
     fn init_drawable(&self, draw_context: &DrawContext) -> Self::D;
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, _visitor: &mut dyn ShapeVisitor) {}
 }
 
-pub struct DrawState<'a> {
-    pub render_pass: RefCell<RenderPass<'a>>,
+/// What a drawable draws with. `'a` is how long the resources set on the
+/// render pass must live, and `'p` how long the render pass is borrowed for.
+pub struct DrawState<'a, 'p> {
+    pub render_pass: RefCell<&'p mut RenderPass<'a>>,
     pub bind_group: &'a BindGroup,
 }
 
 pub struct UpdateState<'a> {
     pub encoder: &'a RefCell<CommandEncoder>,
     pub device: &'a Device,
+    /// Time since the first frame. When recording, this advances by exactly
+    /// one frame interval per frame rather than following the wall clock.
+    pub elapsed: Duration,
+    /// The transform from scene coordinates to clip space for this frame.
+    pub transform: Mat4,
+    /// Size of the render target in pixels.
+    pub size: PhysicalSize<u32>,
+}
+
+impl<'a> UpdateState<'a> {
+    /// The width of one pixel in scene units, assuming the transform scales
+    /// both axes equally in pixels, as `Camera` does.
+    pub fn pixel_size(&self) -> f32 {
+        2. / (self.transform[0] * self.size.width as f32)
+    }
 }
 
 pub trait Drawable {
     fn update(&mut self, _update_state: &UpdateState) {}
 
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>);
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>);
 }
 
 impl Drawable for GroupLayerDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn update(&mut self, update_state: &UpdateState) {
+        for drawable in &mut self.drawables {
+            drawable.update(update_state);
+        }
+    }
+
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         for drawable in &self.drawables {
             drawable.draw(draw_state);
         }
@@ -50,13 +86,19 @@ impl GenericDrawable {
 }
 
 impl Drawable for GenericDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn update(&mut self, update_state: &UpdateState) {
+        self.drawable.update(update_state);
+    }
+
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         self.drawable.draw(draw_state);
     }
 }
 
 pub trait GenericLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> GenericDrawable;
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor);
 }
 
 pub struct GroupLayer {
@@ -75,6 +117,12 @@ impl Layer for GroupLayer {
                 .collect(),
         }
     }
+
+    fn visit_shapes(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        for layer in &self.layers {
+            layer.visit_shapes_generic(size, visitor);
+        }
+    }
 }
 
 pub struct GroupLayerDrawable {
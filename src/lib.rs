@@ -1,40 +1,77 @@
 use std::iter;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use wgpu::util::{BufferInitDescriptor, DeviceExt};
-use wgpu::{
-    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
-    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsage, CommandEncoder,
-    ShaderStage,
-};
-use winit::dpi::PhysicalSize;
 use winit::{
     event::*,
     event_loop::{ControlFlow, EventLoop},
     window::{Window, WindowBuilder},
 };
 
+pub use crate::arc::{Arc, ArcsLayer, ArcsLayerDrawable};
+pub use crate::arrow::{ArrowEnds, ArrowHead, ArrowStyle, ArrowsLayer, ArrowsLayerDrawable};
 pub use crate::color::Color;
+pub use crate::curve::{Bezier, Curve, CurvesLayer, CurvesLayerDrawable};
+pub use crate::dash::{DashPattern, DashUnits, MAX_DASHES};
+pub use crate::ellipse::{Ellipse, EllipsesLayer, EllipsesLayerDrawable};
+pub use crate::error::Error;
+pub use crate::export::pdf::to_pdf;
+pub use crate::export::svg::to_svg;
+pub use crate::export::ShapeVisitor;
 pub use crate::grid::GridLayer;
 pub use crate::hairline::{Hairline, HairlinesLayer, HairlinesLayerDrawable, Orientation};
+use crate::headless::{offscreen_texture, read_texture};
+pub use crate::headless::{render_to_image, Image};
+pub use crate::image::{ImagesLayer, ImagesLayerDrawable, Sprite};
 pub use crate::layer::{
     DrawContext, GenericDrawable, GenericLayer, GroupLayer, GroupLayerDrawable,
 };
-pub use crate::line::{Line, LinesLayer, LinesLayerDrawable};
+pub use crate::line::{Line, LineCap, LinesLayer, LinesLayerDrawable};
+pub use crate::marker::{Marker, MarkersLayer, MarkersLayerDrawable, Symbol};
+pub use crate::mesh::{MeshLayer, MeshLayerDrawable, MeshVertex};
+pub use crate::options::RunOptions;
+pub use crate::polygon::{FillRule, Polygon, PolygonsLayer, PolygonsLayerDrawable};
+pub use crate::polyline::{LineJoin, Polyline, PolylinesLayer, PolylinesLayerDrawable};
+pub use crate::record::Recording;
 pub use crate::rectangle::{Rectangle, RectanglesLayer, RectanglesLayerDrawable};
-use crate::zoom::Mat4;
+use crate::render::{encode_draw, encode_frame, request_device, Multisample};
+pub use crate::render::{FrameState, Renderer};
+pub use crate::text::{Anchor, Font, Text, TextLayer, TextLayerDrawable};
+pub use crate::units::WidthUnits;
+pub use crate::zoom::{Camera, Mat4};
 pub use circle::{Circle, CirclesLayer, CirclesLayerDrawable};
 pub use layer::{DrawState, Drawable, Layer, UpdateState};
-use std::cell::RefCell;
 use zoom::ZoomState;
 
+mod arc;
+mod arrow;
 mod circle;
 mod color;
+mod curve;
+mod dash;
+mod ellipse;
+mod error;
+mod export;
 mod gpu_data;
 mod grid;
 mod hairline;
+mod headless;
+mod image;
 mod layer;
 mod line;
+mod marker;
+mod mesh;
+mod options;
+mod polygon;
+mod polyline;
+mod record;
 mod rectangle;
+mod render;
+#[doc(hidden)]
+pub mod testing;
+mod text;
+mod texture;
+mod units;
 mod zoom;
 
 struct State<T: Layer> {
@@ -44,88 +81,41 @@ struct State<T: Layer> {
     sc_desc: wgpu::SwapChainDescriptor,
     swap_chain: wgpu::SwapChain,
     size: winit::dpi::PhysicalSize<u32>,
-    transform_buffer: Buffer,
-    transform_bind_group: BindGroup,
+    renderer: Renderer,
+    multisample: Multisample,
 
     drawable: T::D,
     zoom_state: ZoomState,
+    start: Instant,
+    clear_color: wgpu::Color,
 }
 
 impl<T: Layer> State<T> {
-    async fn new(window: &Window, layer: T) -> Self {
+    async fn new(window: &Window, layer: T, options: &RunOptions) -> Result<Self, Error> {
         let size = window.inner_size();
 
         let instance = wgpu::Instance::new(wgpu::BackendBit::PRIMARY);
         let surface = unsafe { instance.create_surface(window) };
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::default(),
-                compatible_surface: Some(&surface),
-            })
-            .await
-            .unwrap();
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    features: wgpu::Features::empty(),
-                    limits: wgpu::Limits::default(),
-                },
-                None, // Trace path
-            )
-            .await
-            .unwrap();
+        let (device, queue) =
+            request_device(&instance, Some(&surface), options.power_preference).await?;
 
         let sc_desc = wgpu::SwapChainDescriptor {
             usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            format: options.format,
             width: size.width,
             height: size.height,
-            present_mode: wgpu::PresentMode::Fifo,
+            present_mode: options.present_mode,
         };
         let swap_chain = device.create_swap_chain(&surface, &sc_desc);
 
-        let zoom_state = ZoomState::new(size);
-        let transform = zoom_state.matrix();
-
-        let transform_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Transformation buffer"),
-            contents: bytemuck::cast_slice(&[transform]),
-            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
-        });
-
-        let transform_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-            label: Some("Transformation bind group layout"),
-            entries: &[BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStage::VERTEX,
-                ty: BindingType::Buffer {
-                    ty: BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-        });
-
-        let transform_bind_group = device.create_bind_group(&BindGroupDescriptor {
-            label: Some("Transformation bind group"),
-            layout: &transform_layout,
-            entries: &[BindGroupEntry {
-                binding: 0,
-                resource: transform_buffer.as_entire_binding(),
-            }],
-        });
-
-        let draw_context = DrawContext {
-            transform_layout: &transform_layout,
-            sc_desc: &sc_desc,
-            device: &device,
-        };
-        let drawable = layer.init_drawable(&draw_context);
+        let zoom_state = ZoomState::with_camera(size, options.camera);
+
+        let renderer = Renderer::new(&device, sc_desc.format, options.sample_count);
+        let drawable = renderer.init_drawable(&device, &queue, &layer, size);
 
-        Self {
+        let multisample = Multisample::new(&device, size, sc_desc.format, options.sample_count);
+
+        Ok(Self {
             surface,
             device,
             queue,
@@ -133,10 +123,12 @@ impl<T: Layer> State<T> {
             sc_desc,
             swap_chain,
             drawable,
-            transform_buffer,
-            transform_bind_group,
+            renderer,
+            multisample,
             zoom_state,
-        }
+            start: Instant::now(),
+            clear_color: options.clear_color.into(),
+        })
     }
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -146,6 +138,20 @@ impl<T: Layer> State<T> {
         self.zoom_state.set_size(new_size);
 
         self.swap_chain = self.device.create_swap_chain(&self.surface, &self.sc_desc);
+        self.multisample = Multisample::new(
+            &self.device,
+            new_size,
+            self.sc_desc.format,
+            self.renderer.sample_count(),
+        );
+    }
+
+    fn frame_state(&self) -> FrameState {
+        FrameState {
+            transform: self.zoom_state.matrix(),
+            size: self.size,
+            elapsed: self.start.elapsed(),
+        }
     }
 
     fn input(&mut self, event: &WindowEvent, window: &Window) -> bool {
@@ -155,117 +161,147 @@ impl<T: Layer> State<T> {
     fn render(&mut self) -> Result<(), wgpu::SwapChainError> {
         let frame = self.swap_chain.get_current_frame()?.output;
 
-        let tmp_buffer = self.device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Temporary Buffer"),
-            contents: bytemuck::cast_slice(&self.zoom_state.matrix()),
-            usage: BufferUsage::COPY_SRC,
-        });
+        let encoder = encode_frame(
+            &self.device,
+            &self.renderer,
+            self.frame_state(),
+            &mut self.drawable,
+            self.multisample.target(&frame.view),
+            self.clear_color,
+        );
+
+        self.queue.submit(iter::once(encoder.finish()));
+
+        Ok(())
+    }
+
+    /// Renders the last frame again into an offscreen texture and reads it
+    /// back, since swap chain frames cannot be copied from. The drawable isn't
+    /// updated, so animations don't advance.
+    fn screenshot(&self) -> Result<Image, Error> {
+        let format = self.sc_desc.format;
+        let texture = offscreen_texture(&self.device, self.size, format);
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
         let encoder = self
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
+                label: Some("Screenshot Encoder"),
             });
-
-        let encoder = RefCell::new(encoder);
-
-        encoder.borrow_mut().copy_buffer_to_buffer(
-            &tmp_buffer,
-            0,
-            &self.transform_buffer,
-            0,
-            std::mem::size_of::<Mat4>() as u64,
+        let encoder = encode_draw(
+            encoder,
+            &self.renderer,
+            &self.drawable,
+            self.multisample.target(&view),
+            self.clear_color,
         );
 
-        {
-            let update_state = UpdateState {
-                encoder: &encoder,
-                device: &self.device,
-            };
-            self.drawable.update(&update_state);
-        }
-
-        let mut encoder: CommandEncoder = encoder.into_inner();
-
-        {
-            let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[wgpu::RenderPassColorAttachment {
-                    view: &frame.view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 1.,
-                            g: 1.,
-                            b: 1.,
-                            a: 1.,
-                        }),
-                        store: true,
-                    },
-                }],
-                depth_stencil_attachment: None,
-            });
+        read_texture(
+            &self.device,
+            &self.queue,
+            encoder,
+            &texture,
+            self.size,
+            format,
+        )
+    }
 
-            let draw_state = DrawState {
-                render_pass: RefCell::new(render_pass),
-                bind_group: &self.transform_bind_group,
-            };
+    fn save_screenshot(&mut self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let path = PathBuf::from(format!("vecdraw-{}.png", timestamp));
 
-            self.drawable.draw(&draw_state);
+        match self.screenshot().and_then(|image| image.save_png(&path)) {
+            Ok(()) => eprintln!("Saved screenshot to {}", path.display()),
+            Err(e) => eprintln!("Failed to save screenshot: {}", e),
         }
+    }
+}
 
-        self.queue.submit(iter::once(encoder.finish()));
+pub fn run_event_loop<T: 'static + Layer>(layer: T) {
+    run_event_loop_with_options(layer, RunOptions::default());
+}
 
-        Ok(())
+pub fn run_event_loop_with_options<T: 'static + Layer>(layer: T, options: RunOptions) {
+    if let Err(e) = try_run_event_loop_with_options(layer, options) {
+        panic!("{}", e);
     }
 }
 
-pub fn run_event_loop<T: 'static + Layer>(layer: T) {
-    env_logger::init();
-    let event_loop = EventLoop::new();
+/// Like `run_event_loop`, but returns an error instead of panicking if the
+/// window or graphics device can't be created. Only returns on failure.
+///
+/// This creates the process's event loop, and winit only allows one per
+/// process, so it can't be called again even after failing. Callers that need
+/// to create the event loop themselves should use `try_run_on_event_loop`.
+pub fn try_run_event_loop<T: 'static + Layer>(layer: T) -> Result<(), Error> {
+    try_run_event_loop_with_options(layer, RunOptions::default())
+}
+
+pub fn try_run_event_loop_with_options<T: 'static + Layer>(
+    layer: T,
+    options: RunOptions,
+) -> Result<(), Error> {
+    try_run_on_event_loop(EventLoop::new(), layer, options)
+}
+
+/// Like `try_run_event_loop_with_options`, but runs on an event loop created
+/// by the caller, for instance one built with platform-specific options.
+/// Events sent through the loop's proxies are ignored.
+pub fn try_run_on_event_loop<T: 'static + Layer, E: 'static>(
+    event_loop: EventLoop<E>,
+    layer: T,
+    options: RunOptions,
+) -> Result<(), Error> {
+    // The caller may have set up logging already.
+    let _ = env_logger::try_init();
     let window = WindowBuilder::new()
-        .with_title("Shape Drawing Demo")
-        .with_inner_size(PhysicalSize {
-            width: 600,
-            height: 600,
-        })
-        .build(&event_loop)
-        .unwrap();
+        .with_title(&options.title)
+        .with_inner_size(options.size)
+        .build(&event_loop)?;
 
     use futures::executor::block_on;
 
-    let mut state: State<T> = block_on(State::new(&window, layer));
+    let mut state: State<T> = block_on(State::new(&window, layer, &options))?;
+    let escape_quits = options.escape_quits;
 
     event_loop.run(move |event, _, control_flow| {
         match event {
             Event::WindowEvent {
                 ref event,
                 window_id,
-            } if window_id == window.id() => {
-                if !state.input(event, &window) {
-                    match event {
-                        WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                        WindowEvent::KeyboardInput {
-                            input:
-                                KeyboardInput {
-                                    state: ElementState::Pressed,
-                                    virtual_keycode: Some(VirtualKeyCode::Escape),
-                                    ..
-                                },
+            } if window_id == window.id() && !state.input(event, &window) => match event {
+                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::Escape),
+                            ..
+                        },
+                    ..
+                } if escape_quits => *control_flow = ControlFlow::Exit,
+                WindowEvent::KeyboardInput {
+                    input:
+                        KeyboardInput {
+                            state: ElementState::Pressed,
+                            virtual_keycode: Some(VirtualKeyCode::F12),
                             ..
-                        } => *control_flow = ControlFlow::Exit,
-                        WindowEvent::Resized(physical_size) => {
-                            state.resize(*physical_size);
-                            window.request_redraw();
-                        }
-                        WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
-                            state.resize(**new_inner_size);
-                            window.request_redraw();
-                        }
-                        _ => {}
-                    }
+                        },
+                    ..
+                } => state.save_screenshot(),
+                WindowEvent::Resized(physical_size) => {
+                    state.resize(*physical_size);
+                    window.request_redraw();
                 }
-            }
+                WindowEvent::ScaleFactorChanged { new_inner_size, .. } => {
+                    state.resize(**new_inner_size);
+                    window.request_redraw();
+                }
+                _ => {}
+            },
             Event::RedrawRequested(_) => {
                 match state.render() {
                     Ok(_) => {}
@@ -2,12 +2,38 @@ use crate::layer::{DrawContext, DrawState, Drawable, Layer};
 use crate::{GenericDrawable, GenericLayer};
 
 use crate::color::Color;
+use crate::dash::{DashBinding, DashPattern};
+use crate::export::ShapeVisitor;
 use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::units::WidthUnits;
 use std::borrow::Cow;
 use wgpu::{
     BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
     VertexBufferLayout,
 };
+use winit::dpi::PhysicalSize;
+
+/// How the ends of a line are drawn.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum LineCap {
+    /// Ends exactly at the endpoints.
+    #[default]
+    Butt,
+    /// Ends in a half circle around each endpoint.
+    Round,
+    /// Extends past each endpoint by the line's `width`.
+    Square,
+}
+
+impl LineCap {
+    fn fragment_entry_point(self) -> &'static str {
+        match self {
+            LineCap::Butt => "fs_butt",
+            LineCap::Round => "fs_round",
+            LineCap::Square => "fs_square",
+        }
+    }
+}
 
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
@@ -55,24 +81,53 @@ impl GpuSerializable for Line {
 
 pub struct LinesLayer {
     data: Vec<Line>,
+    cap: LineCap,
+    dash: Option<DashPattern>,
+    units: WidthUnits,
 }
 
 impl LinesLayer {
     pub fn new(data: Vec<Line>) -> Self {
-        LinesLayer { data }
+        LinesLayer {
+            data,
+            cap: LineCap::default(),
+            dash: None,
+            units: WidthUnits::default(),
+        }
+    }
+
+    /// Sets the cap drawn at both ends of every line in the layer.
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Dashes every line in the layer, restarting the pattern at each line's
+    /// `start`.
+    pub fn with_dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+
+    /// Sets the units of the widths of every line in the layer.
+    pub fn with_width_units(mut self, units: WidthUnits) -> Self {
+        self.units = units;
+        self
     }
 }
 
 pub struct LinesLayerDrawable {
     render_pipeline: RenderPipeline,
+    dash: DashBinding,
     pub instance_buffer: GpuBuffer<Line>,
 }
 
 impl Drawable for LinesLayerDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         let mut render_pass = draw_state.render_pass.borrow_mut();
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_bind_group(1, &self.dash.bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.instance_buffer.all());
         render_pass.draw(0..6, 0..self.instance_buffer.len());
     }
@@ -86,19 +141,25 @@ impl Layer for LinesLayer {
             device,
             sc_desc,
             transform_layout,
+            sample_count,
+            ..
         } = *draw_context;
         let instance_buffer = GpuBuffer::new(&self.data, device);
+        let dash = DashBinding::new(device, self.dash.as_ref());
 
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[transform_layout],
+                bind_group_layouts: &[transform_layout, &dash.layout],
                 push_constant_ranges: &[],
             });
 
         let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
             label: None,
-            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            source: ShaderSource::Wgsl(Cow::Owned(self.units.shader_source(concat!(
+                include_str!("../dash.wgsl"),
+                include_str!("shader.wgsl")
+            )))),
             flags: Default::default(),
         });
 
@@ -112,7 +173,7 @@ impl Layer for LinesLayer {
             },
             fragment: Some(wgpu::FragmentState {
                 module: &shader_module,
-                entry_point: "fs_main",
+                entry_point: self.cap.fragment_entry_point(),
                 targets: &[wgpu::ColorTargetState {
                     format: sc_desc.format,
                     write_mask: wgpu::ColorWrite::ALL,
@@ -135,7 +196,7 @@ impl Layer for LinesLayer {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -143,13 +204,22 @@ impl Layer for LinesLayer {
 
         LinesLayerDrawable {
             render_pipeline,
+            dash,
             instance_buffer,
         }
     }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.lines(&self.data, self.cap, self.dash.as_ref(), self.units);
+    }
 }
 
 impl GenericLayer for LinesLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
         GenericDrawable::new(self.init_drawable(draw_context))
     }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
 }
@@ -1,16 +1,47 @@
 struct VertexOutput {
     [[location(0)]] color: vec4<f32>;
-    [[location(1)]] edge: vec2<f32>;
+    // Position relative to the line, as (distance along it from `start`,
+    // signed distance from its center line).
+    [[location(1)]] local: vec2<f32>;
+    // (length, width) of the line.
+    [[location(2)]] extent: vec2<f32>;
     [[builtin(position)]] position: vec4<f32>;
 };
 
-[[block]]
-struct Uniforms {
-    transform: mat4x4<f32>;
-};
+// (along, across) for each corner, where along is 0 at `start` and 1 at `end`.
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(0., -1.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(1., 1.),
+);
+
+fn vertex(in_vertex_index: u32, start: vec2<f32>, end: vec2<f32>, color: vec4<f32>,
+    width: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.color = color;
+
+    let len: f32 = length(end - start);
+    var dir: vec2<f32> = vec2<f32>(1., 0.);
+    if (len > 0.) {
+        dir = (end - start) / len;
+    }
+    let perp: vec2<f32> = vec2<f32>(-dir.y, dir.x);
 
-[[group(0), binding(0)]]
-var uniforms: Uniforms;
+    // The quad extends `width` past both endpoints to leave room for caps.
+    let corner: vec2<f32> = corners[in_vertex_index];
+    let along: f32 = corner.x * (len + 2. * width) - width;
+    let across: f32 = corner.y * width;
+
+    out.local = vec2<f32>(along, across);
+    out.extent = vec2<f32>(len, width);
+    out.position = uniforms.transform * vec4<f32>(start + dir * along + perp * across, 0., 1.);
+
+    return out;
+}
 
 [[stage(vertex)]]
 fn vs_main(
@@ -20,54 +51,49 @@ fn vs_main(
     [[location(2)]] color: vec4<f32>,
     [[location(3)]] width: f32,
 ) -> VertexOutput {
-    var out: VertexOutput;
-    out.color = color;
+    return vertex(in_vertex_index, start, end, color, width * width_scale());
+}
 
-    let line: vec2<f32> = normalize(end - start);
-    let perp: vec2<f32> = vec2<f32>(line.y, -line.x);
-
-    switch (i32(in_vertex_index)) {
-        case 0: {
-            let c: vec2<f32> = start - perp * width;
-            out.position = vec4<f32>(c, 0., 1.);
-            out.edge = vec2<f32>(0., 0.);
-        }
-        case 1: {
-            fallthrough;
-        }
-        case 3: {
-            let c: vec2<f32> = start + perp * width;
-            out.position = vec4<f32>(c, 0., 1.);
-            out.edge = vec2<f32>(0., 1.);
-        }
-        case 2: {
-            fallthrough;
-        }
-        case 4: {
-            let c: vec2<f32> = end - perp * width;
-            out.position = vec4<f32>(c, 0., 1.);
-            out.edge = vec2<f32>(1., 0.);
-        }
-        case 5: {
-            let c: vec2<f32> = end + perp * width;
-            out.position = vec4<f32>(c, 0., 1.);
-            out.edge = vec2<f32>(0., 0.);
-        }
-    }
+// Signed distance along the line from the fragment to the nearest drawn part
+// of it, before caps are added.
+fn along_distance(along: f32, len: f32) -> f32 {
+    let scene_per_pixel: f32 = length(vec2<f32>(dpdx(along), dpdy(along)));
+    let line: f32 = abs(along - len / 2.) - len / 2.;
 
-    out.position = uniforms.transform * out.position;
+    return max(line, dash_distance(along, scene_per_pixel));
+}
 
-    return out;
+// Turns a signed distance from the edge of the line (negative inside) into
+// a color, fading out over one pixel.
+fn shade(color: vec4<f32>, d: f32) -> vec4<f32> {
+    let alpha: f32 = clamp(0.5 - d / fwidth(d), 0., 1.);
+
+    return color * alpha;
 }
 
 [[stage(fragment)]]
-fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    let dx: f32 = fwidth(in.edge.x);
-    let dy: f32 = fwidth(in.edge.y);
+fn fs_butt(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let d: vec2<f32> = vec2<f32>(
+        along_distance(in.local.x, in.extent.x),
+        abs(in.local.y) - in.extent.y,
+    );
 
-    let xcov: f32 = min(clamp(0., 1., in.edge.x / dx), clamp(0., 1., (1. - in.edge.x) / dx));
-    let ycov: f32 = min(clamp(0., 1., in.edge.y / dy), clamp(0., 1., (1. - in.edge.y) / dy));
-    let alpha: f32 = xcov * ycov;
+    return shade(in.color, max(d.x, d.y));
+}
 
-    return in.color * alpha;
-}
\ No newline at end of file
+[[stage(fragment)]]
+fn fs_square(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let d: vec2<f32> = vec2<f32>(
+        along_distance(in.local.x, in.extent.x) - in.extent.y,
+        abs(in.local.y) - in.extent.y,
+    );
+
+    return shade(in.color, max(d.x, d.y));
+}
+
+[[stage(fragment)]]
+fn fs_round(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let along: f32 = max(along_distance(in.local.x, in.extent.x), 0.);
+
+    return shade(in.color, length(vec2<f32>(along, in.local.y)) - in.extent.y);
+}
@@ -0,0 +1,278 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use std::borrow::Cow;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+/// Half the thickness of the arms of `Plus` and `Cross`, relative to half
+/// the marker size. Must match `shader.wgsl`.
+const ARM: f32 = 0.2;
+
+/// Radius of the inner corners of `Star`, relative to its points. Must match
+/// `shader.wgsl`.
+const STAR_INNER: f32 = 0.45;
+
+/// The shape of a marker, centered on its position. Each fits in a square
+/// of the marker's size, except `Triangle`, whose sides are the marker's
+/// size long and whose top point reaches 2/√3 times half the size above the
+/// center.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Symbol {
+    #[default]
+    Circle,
+    Square,
+    /// An equilateral triangle pointing up.
+    Triangle,
+    Diamond,
+    /// A diagonal cross, like an X.
+    Cross,
+    Plus,
+    /// A five-pointed star pointing up.
+    Star,
+}
+
+impl Symbol {
+    /// The outline of every symbol but `Circle`, for a marker `size` wide,
+    /// relative to its center with Y increasing going up.
+    pub(crate) fn outline(self, size: f32) -> Vec<[f32; 2]> {
+        let r = size / 2.;
+        let polar = |radius: f32, angle: f32| [radius * angle.cos(), radius * angle.sin()];
+        let plus = |rotation: f32| {
+            let (sin, cos) = rotation.sin_cos();
+            let t = ARM * r;
+            [
+                [t, t],
+                [t, r],
+                [-t, r],
+                [-t, t],
+                [-r, t],
+                [-r, -t],
+                [-t, -t],
+                [-t, -r],
+                [t, -r],
+                [t, -t],
+                [r, -t],
+                [r, t],
+            ]
+            .iter()
+            .map(|&[x, y]| [cos * x - sin * y, sin * x + cos * y])
+            .collect()
+        };
+
+        match self {
+            Symbol::Circle => Vec::new(),
+            Symbol::Square => vec![[-r, -r], [r, -r], [r, r], [-r, r]],
+            Symbol::Triangle => {
+                let h = 3f32.sqrt() * r;
+                vec![[-r, -h / 3.], [r, -h / 3.], [0., 2. * h / 3.]]
+            }
+            Symbol::Diamond => vec![[0., -r], [r, 0.], [0., r], [-r, 0.]],
+            Symbol::Cross => plus(FRAC_PI_4),
+            Symbol::Plus => plus(0.),
+            Symbol::Star => (0..10)
+                .map(|i| {
+                    let radius = if i % 2 == 0 { r } else { STAR_INNER * r };
+                    polar(radius, FRAC_PI_2 + i as f32 * PI / 5.)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A symbol drawn at the same size on screen however far the view is zoomed.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Marker {
+    pub position: [f32; 2],
+    pub color: Color,
+    /// Width of the symbol, in pixels.
+    pub size: f32,
+    pub symbol: Symbol,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+struct MarkerInstance {
+    position: [f32; 2],
+    color: Color,
+    size: f32,
+    symbol: u32,
+}
+
+impl From<&Marker> for MarkerInstance {
+    fn from(marker: &Marker) -> Self {
+        MarkerInstance {
+            position: marker.position,
+            color: marker.color,
+            size: marker.size,
+            symbol: marker.symbol as u32,
+        }
+    }
+}
+
+impl GpuSerializable for MarkerInstance {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MarkerInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Uint32,
+                },
+            ],
+        }
+    }
+}
+
+fn instances(data: &[Marker]) -> Vec<MarkerInstance> {
+    data.iter().map(MarkerInstance::from).collect()
+}
+
+pub struct MarkersLayer {
+    data: Vec<Marker>,
+}
+
+impl MarkersLayer {
+    pub fn new(data: Vec<Marker>) -> Self {
+        MarkersLayer { data }
+    }
+}
+
+pub struct MarkersLayerDrawable {
+    render_pipeline: RenderPipeline,
+    instance_buffer: GpuBuffer<MarkerInstance>,
+}
+
+impl MarkersLayerDrawable {
+    /// Replaces the markers drawn by this drawable.
+    pub fn set_markers(&mut self, data: &[Marker], update_state: &UpdateState) {
+        self.instance_buffer.update(
+            &instances(data),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+    }
+}
+
+impl Drawable for MarkersLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.all());
+        render_pass.draw(0..6, 0..self.instance_buffer.len());
+    }
+}
+
+impl GenericLayer for MarkersLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+impl Layer for MarkersLayer {
+    type D = MarkersLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> MarkersLayerDrawable {
+        let DrawContext {
+            device,
+            sc_desc,
+            transform_layout,
+            sample_count,
+            ..
+        } = *draw_context;
+        let instance_buffer = GpuBuffer::new(&instances(&self.data), device);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[MarkerInstance::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        MarkersLayerDrawable {
+            render_pipeline,
+            instance_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.markers(&self.data);
+    }
+}
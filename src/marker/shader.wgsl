@@ -0,0 +1,140 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    // Offset from the center of the marker, in pixels with Y increasing
+    // going up.
+    [[location(1)]] coord: vec2<f32>;
+    // (half the size of the marker, symbol) of the marker.
+    [[location(2)]] shape: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+    viewport: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+// These must match the order of `Symbol`.
+let CIRCLE: i32 = 0;
+let SQUARE: i32 = 1;
+let TRIANGLE: i32 = 2;
+let DIAMOND: i32 = 3;
+let CROSS: i32 = 4;
+let PLUS: i32 = 5;
+let STAR: i32 = 6;
+
+// These must match `ARM` and `STAR_INNER` in mod.rs.
+let ARM: f32 = 0.2;
+let STAR_INNER: f32 = 0.45;
+
+let PI: f32 = 3.14159265358979;
+
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(-1., -1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., 1.),
+);
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] color: vec4<f32>,
+    [[location(2)]] size: f32,
+    [[location(3)]] symbol: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let r: f32 = size / 2.;
+    // The top vertex of the triangle is 2 / sqrt(3) times r from the center,
+    // and one more pixel leaves room for anti-aliasing.
+    let extent: f32 = r * 1.2 + 1.;
+
+    out.color = color;
+    out.coord = corners[in_vertex_index] * extent;
+    out.shape = vec2<f32>(r, f32(symbol));
+
+    let center: vec4<f32> = uniforms.transform * vec4<f32>(position, 0., 1.);
+    let offset: vec2<f32> = out.coord * 2. / uniforms.viewport * center.w;
+    out.position = center + vec4<f32>(offset, 0., 0.);
+
+    return out;
+}
+
+fn sd_box(p: vec2<f32>, b: vec2<f32>) -> f32 {
+    let d: vec2<f32> = abs(p) - b;
+    return length(max(d, vec2<f32>(0., 0.))) + min(max(d.x, d.y), 0.);
+}
+
+fn sd_plus(p: vec2<f32>, r: f32) -> f32 {
+    let t: f32 = ARM * r;
+    return min(sd_box(p, vec2<f32>(r, t)), sd_box(p, vec2<f32>(t, r)));
+}
+
+// An equilateral triangle with sides 2r long, centered on its centroid.
+fn sd_triangle(p: vec2<f32>, r: f32) -> f32 {
+    let k: f32 = sqrt(3.);
+    var q: vec2<f32> = vec2<f32>(abs(p.x) - r, p.y + r / k);
+    if (q.x + k * q.y > 0.) {
+        q = vec2<f32>(q.x - k * q.y, -k * q.x - q.y) / 2.;
+    }
+    q.x = q.x - clamp(q.x, -2. * r, 0.);
+    return -length(q) * sign(q.y);
+}
+
+fn sd_star(p: vec2<f32>, r: f32) -> f32 {
+    let an: f32 = PI / 5.;
+    let acs: vec2<f32> = vec2<f32>(cos(an), sin(an));
+    let edge: vec2<f32> = acs - vec2<f32>(STAR_INNER, 0.);
+    let ecs: vec2<f32> = normalize(edge);
+
+    // Fold the plane into the half of one point of the star that lies
+    // between its tip, along `acs`, and the inner corner on the X axis.
+    let bn: f32 = (atan2(p.x, p.y) + 2. * PI) % (2. * an) - an;
+    var q: vec2<f32> = length(p) * vec2<f32>(cos(bn), abs(sin(bn)));
+
+    q = q - r * acs;
+    q = q + ecs * clamp(-dot(q, ecs), 0., r * length(edge));
+    return length(q) * sign(q.x);
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let p: vec2<f32> = in.coord;
+    let r: f32 = in.shape.x;
+    let symbol: i32 = i32(round(in.shape.y));
+
+    var d: f32 = length(p) - r;
+    if (symbol == SQUARE) {
+        d = sd_box(p, vec2<f32>(r, r));
+    }
+    if (symbol == TRIANGLE) {
+        d = sd_triangle(p, r);
+    }
+    if (symbol == DIAMOND) {
+        d = (abs(p.x) + abs(p.y) - r) / sqrt(2.);
+    }
+    if (symbol == CROSS) {
+        d = sd_plus(vec2<f32>(p.x + p.y, p.y - p.x) / sqrt(2.), r);
+    }
+    if (symbol == PLUS) {
+        d = sd_plus(p, r);
+    }
+    if (symbol == STAR) {
+        d = sd_star(p, r);
+    }
+
+    // Distances are in pixels, so no derivatives are needed to anti-alias.
+    let alpha: f32 = clamp(0.5 - d, 0., 1.);
+
+    if (alpha < 0.01) {
+        discard;
+    }
+
+    return in.color * alpha;
+}
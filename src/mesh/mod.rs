@@ -0,0 +1,275 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::error::Error;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use std::borrow::Cow;
+use wgpu::{
+    BlendComponent, BlendState, BufferUsage, IndexFormat, RenderPipeline, ShaderModuleDescriptor,
+    ShaderSource, VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+/// A corner of one or more triangles in a `MeshLayer`. Colors are
+/// interpolated across each triangle.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
+pub struct MeshVertex {
+    pub position: [f32; 2],
+    pub color: Color,
+}
+
+impl GpuSerializable for MeshVertex {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<MeshVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+            ],
+        }
+    }
+}
+
+// `usize::is_multiple_of` needs a newer compiler than the crate otherwise
+// does.
+#[allow(clippy::manual_is_multiple_of)]
+fn check_indices(vertices: &[MeshVertex], indices: &[u32]) -> Result<(), Error> {
+    if indices.len() % 3 != 0 {
+        return Err(Error::MeshIndexCount(indices.len()));
+    }
+    match indices.iter().find(|&&i| i as usize >= vertices.len()) {
+        Some(&i) => Err(Error::MissingVertex(i)),
+        None => Ok(()),
+    }
+}
+
+/// The corners of each triangle of a mesh and the average of their colors,
+/// for output formats without per-vertex colors.
+pub(crate) fn flat_triangles<'a>(
+    vertices: &'a [MeshVertex],
+    indices: &'a [u32],
+) -> impl Iterator<Item = ([[f32; 2]; 3], Color)> + 'a {
+    indices.chunks(3).map(move |triangle| {
+        let corners = [
+            vertices[triangle[0] as usize],
+            vertices[triangle[1] as usize],
+            vertices[triangle[2] as usize],
+        ];
+
+        let mut rgba = [0u8; 4];
+        for (i, channel) in rgba.iter_mut().enumerate() {
+            let sum: u32 = corners
+                .iter()
+                .map(|vertex| vertex.color.to_rgba()[i] as u32)
+                .sum();
+            *channel = ((sum + 1) / 3) as u8;
+        }
+
+        (
+            [
+                corners[0].position,
+                corners[1].position,
+                corners[2].position,
+            ],
+            Color(u32::from_ne_bytes(rgba)),
+        )
+    })
+}
+
+/// Draws triangles given by groups of three indices into `vertices`.
+pub struct MeshLayer {
+    vertices: Vec<MeshVertex>,
+    indices: Vec<u32>,
+}
+
+impl MeshLayer {
+    /// Fails if the number of indices is not a multiple of three, or if any
+    /// index is out of range.
+    pub fn new(vertices: Vec<MeshVertex>, indices: Vec<u32>) -> Result<Self, Error> {
+        check_indices(&vertices, &indices)?;
+        Ok(MeshLayer { vertices, indices })
+    }
+}
+
+pub struct MeshLayerDrawable {
+    render_pipeline: RenderPipeline,
+    vertex_buffer: GpuBuffer<MeshVertex>,
+    index_buffer: GpuBuffer<u32>,
+}
+
+impl MeshLayerDrawable {
+    /// Replaces the mesh drawn by this drawable. Fails, leaving the mesh
+    /// unchanged, under the same conditions as `MeshLayer::new`.
+    pub fn set_mesh(
+        &mut self,
+        vertices: &[MeshVertex],
+        indices: &[u32],
+        update_state: &UpdateState,
+    ) -> Result<(), Error> {
+        check_indices(vertices, indices)?;
+        self.vertex_buffer.update(
+            vertices,
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+        self.index_buffer.update(
+            indices,
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+        Ok(())
+    }
+}
+
+impl Drawable for MeshLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.all());
+        render_pass.set_index_buffer(self.index_buffer.all(), IndexFormat::Uint32);
+        render_pass.draw_indexed(0..self.index_buffer.len(), 0, 0..1);
+    }
+}
+
+impl GenericLayer for MeshLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+impl Layer for MeshLayer {
+    type D = MeshLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> MeshLayerDrawable {
+        let DrawContext {
+            device,
+            sc_desc,
+            transform_layout,
+            sample_count,
+            ..
+        } = *draw_context;
+        let vertex_buffer = GpuBuffer::new(&self.vertices, device);
+        let index_buffer = GpuBuffer::new_with_usage(&self.indices, BufferUsage::INDEX, device);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[MeshVertex::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        MeshLayerDrawable {
+            render_pipeline,
+            vertex_buffer,
+            index_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.mesh(&self.vertices, &self.indices);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertices(count: usize) -> Vec<MeshVertex> {
+        vec![MeshVertex::default(); count]
+    }
+
+    #[test]
+    fn valid_indices() {
+        assert!(check_indices(&vertices(4), &[0, 1, 2, 2, 1, 3]).is_ok());
+    }
+
+    #[test]
+    fn partial_triangle() {
+        assert!(matches!(
+            check_indices(&vertices(4), &[0, 1, 2, 3]),
+            Err(Error::MeshIndexCount(4))
+        ));
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        assert!(matches!(
+            check_indices(&vertices(3), &[0, 1, 3]),
+            Err(Error::MissingVertex(3))
+        ));
+    }
+
+    #[test]
+    fn empty_mesh() {
+        assert!(check_indices(&[], &[]).is_ok());
+        assert!(matches!(
+            check_indices(&[], &[0, 0, 0]),
+            Err(Error::MissingVertex(0))
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+use wgpu::{PowerPreference, PresentMode, TextureFormat};
+use winit::dpi::PhysicalSize;
+
+use crate::color::Color;
+use crate::zoom::Camera;
+
+/// Window and renderer configuration for `run_event_loop_with_options`.
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    pub(crate) title: String,
+    pub(crate) size: PhysicalSize<u32>,
+    pub(crate) clear_color: Color,
+    pub(crate) present_mode: PresentMode,
+    pub(crate) format: TextureFormat,
+    pub(crate) power_preference: PowerPreference,
+    pub(crate) sample_count: u32,
+    pub(crate) camera: Camera,
+    pub(crate) escape_quits: bool,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions {
+            title: "Shape Drawing Demo".to_string(),
+            size: PhysicalSize::new(600, 600),
+            clear_color: Color::WHITE,
+            present_mode: PresentMode::Fifo,
+            format: TextureFormat::Bgra8UnormSrgb,
+            power_preference: PowerPreference::default(),
+            sample_count: 1,
+            camera: Camera::default(),
+            escape_quits: true,
+        }
+    }
+}
+
+impl RunOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = PhysicalSize::new(width, height);
+        self
+    }
+
+    pub fn with_clear_color(mut self, clear_color: Color) -> Self {
+        self.clear_color = clear_color;
+        self
+    }
+
+    pub fn with_present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.present_mode = present_mode;
+        self
+    }
+
+    pub fn with_format(mut self, format: TextureFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_power_preference(mut self, power_preference: PowerPreference) -> Self {
+        self.power_preference = power_preference;
+        self
+    }
+
+    /// Sets the number of samples per pixel used for multisample
+    /// anti-aliasing. Adapters generally support 1 and 4.
+    pub fn with_sample_count(mut self, sample_count: u32) -> Self {
+        self.sample_count = sample_count;
+        self
+    }
+
+    pub fn with_camera(mut self, camera: Camera) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    pub fn with_escape_quits(mut self, escape_quits: bool) -> Self {
+        self.escape_quits = escape_quits;
+        self
+    }
+}
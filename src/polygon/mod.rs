@@ -0,0 +1,210 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::polygon::tessellate::{tessellate, FillVertex};
+use std::borrow::Cow;
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+mod tessellate;
+
+/// Decides which regions of a self-overlapping polygon are filled, from the
+/// number of times its outline winds around them. As in SVG, holes under
+/// the non-zero rule must wind the opposite way from the outline around
+/// them.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Polygon {
+    /// Closed rings of points, implicitly joined from the last point back to
+    /// the first. Rings after the first are usually holes.
+    pub rings: Vec<Vec<[f32; 2]>>,
+    pub color: Color,
+    pub fill_rule: FillRule,
+}
+
+impl GpuSerializable for FillVertex {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<FillVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+            ],
+        }
+    }
+}
+
+fn fill_vertices(data: &[Polygon]) -> Vec<FillVertex> {
+    let mut vertices = Vec::new();
+    for polygon in data {
+        tessellate(
+            &polygon.rings,
+            polygon.fill_rule,
+            polygon.color,
+            &mut vertices,
+        );
+    }
+    vertices
+}
+
+pub struct PolygonsLayer {
+    data: Vec<Polygon>,
+}
+
+impl PolygonsLayer {
+    pub fn new(data: Vec<Polygon>) -> Self {
+        PolygonsLayer { data }
+    }
+}
+
+pub struct PolygonsLayerDrawable {
+    render_pipeline: RenderPipeline,
+    vertex_buffer: GpuBuffer<FillVertex>,
+}
+
+impl PolygonsLayerDrawable {
+    /// Replaces the polygons drawn by this drawable, tessellating them again.
+    pub fn set_polygons(&mut self, data: &[Polygon], update_state: &UpdateState) {
+        self.vertex_buffer.update(
+            &fill_vertices(data),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+    }
+}
+
+impl Drawable for PolygonsLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.all());
+        render_pass.draw(0..self.vertex_buffer.len(), 0..1);
+    }
+}
+
+fn fill_pipeline(draw_context: &DrawContext) -> RenderPipeline {
+    let DrawContext {
+        device,
+        sc_desc,
+        transform_layout,
+        sample_count,
+        ..
+    } = *draw_context;
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Render Pipeline Layout"),
+        bind_group_layouts: &[transform_layout],
+        push_constant_ranges: &[],
+    });
+
+    let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+        label: None,
+        source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+        flags: Default::default(),
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_module,
+            entry_point: "vs_main",
+            buffers: &[FillVertex::buffer_layout()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_module,
+            entry_point: "fs_main",
+            targets: &[wgpu::ColorTargetState {
+                format: sc_desc.format,
+                write_mask: wgpu::ColorWrite::ALL,
+                blend: Some(BlendState {
+                    color: BlendComponent::OVER,
+                    alpha: BlendComponent::REPLACE,
+                }),
+            }],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+    })
+}
+
+impl Layer for PolygonsLayer {
+    type D = PolygonsLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> PolygonsLayerDrawable {
+        let vertex_buffer = GpuBuffer::new(&fill_vertices(&self.data), draw_context.device);
+
+        PolygonsLayerDrawable {
+            render_pipeline: fill_pipeline(draw_context),
+            vertex_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.polygons(&self.data);
+    }
+}
+
+impl GenericLayer for PolygonsLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
@@ -0,0 +1,48 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    // Distances to the sides of the piece being drawn, positive inside.
+    // Sides that aren't part of the polygon's outline are very far away.
+    [[location(1)]] edges: vec4<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main(
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] edges: vec4<f32>,
+    [[location(2)]] color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.color = color;
+    out.edges = edges;
+    out.position = uniforms.transform * vec4<f32>(position, 0., 1.);
+
+    return out;
+}
+
+// Coverage of a pixel by the inside of one side, from its distance in scene
+// units.
+fn coverage(d: f32) -> f32 {
+    let per_pixel: f32 = length(vec2<f32>(dpdx(d), dpdy(d)));
+
+    return clamp(0.5 + d / per_pixel, 0., 1.);
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let alpha: f32 = coverage(in.edges.x)
+        * coverage(in.edges.y)
+        * coverage(in.edges.z)
+        * coverage(in.edges.w);
+
+    return in.color * alpha;
+}
@@ -0,0 +1,492 @@
+//! Tessellates polygons with arbitrary self-intersections into anti-aliased
+//! triangles.
+//!
+//! Edges are first split wherever they cross, and the plane is cut into
+//! horizontal slabs at every remaining vertex. Within a slab no two edges
+//! cross, so sorting them by x and accumulating winding numbers yields the
+//! filled spans as trapezoids. Each trapezoid is then cut into pieces whose
+//! sides are each entirely on or entirely off the polygon's outline, and
+//! every vertex records its distance to the sides that are on it, which the
+//! fragment shader turns into coverage.
+
+use crate::color::Color;
+use crate::polygon::FillRule;
+
+type Point = [f64; 2];
+
+/// Distance given for sides that aren't part of the outline, so that they
+/// never fade.
+const FAR: f32 = 1e30;
+
+const EPSILON: f64 = 1e-9;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+pub(crate) struct FillVertex {
+    pub position: [f32; 2],
+    /// Distances to the left, right, top and bottom sides of the piece the
+    /// vertex belongs to, positive inside.
+    pub edges: [f32; 4],
+    pub color: Color,
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Adds the points where segments cross or touch to `splits`, indexed like
+/// `segments`.
+///
+/// Every pair of segments is tested, so this takes O(n²) time in the number
+/// of edges and dominates tessellation for polygons with thousands of them.
+fn find_intersections(segments: &[(Point, Point)], splits: &mut [Vec<Point>]) {
+    for i in 0..segments.len() {
+        let (p, p_end) = segments[i];
+        let r = sub(p_end, p);
+
+        for j in i + 1..segments.len() {
+            let (q, q_end) = segments[j];
+            let s = sub(q_end, q);
+            let qp = sub(q, p);
+            let denominator = cross(r, s);
+
+            if denominator.abs() > EPSILON * (r[0].abs() + r[1].abs()) * (s[0].abs() + s[1].abs()) {
+                let t = cross(qp, s) / denominator;
+                let u = cross(qp, r) / denominator;
+                if (0. ..=1.).contains(&t) && (0. ..=1.).contains(&u) {
+                    let point = [p[0] + t * r[0], p[1] + t * r[1]];
+                    if t > 0. && t < 1. {
+                        splits[i].push(point);
+                    }
+                    if u > 0. && u < 1. {
+                        splits[j].push(point);
+                    }
+                }
+            } else if cross(qp, r).abs()
+                <= EPSILON * (r[0].abs() + r[1].abs()) * (qp[0].abs() + qp[1].abs())
+            {
+                // Collinear segments split each other at the endpoints that
+                // fall inside the other.
+                let r_length = r[0] * r[0] + r[1] * r[1];
+                let s_length = s[0] * s[0] + s[1] * s[1];
+                for &point in &[q, q_end] {
+                    let t = (sub(point, p)[0] * r[0] + sub(point, p)[1] * r[1]) / r_length;
+                    if t > 0. && t < 1. {
+                        splits[i].push(point);
+                    }
+                }
+                for &point in &[p, p_end] {
+                    let u = (sub(point, q)[0] * s[0] + sub(point, q)[1] * s[1]) / s_length;
+                    if u > 0. && u < 1. {
+                        splits[j].push(point);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A non-horizontal edge, stored bottom to top.
+struct Edge {
+    lower: Point,
+    upper: Point,
+    winding: i32,
+}
+
+impl Edge {
+    fn x_at(&self, y: f64) -> f64 {
+        if y <= self.lower[1] {
+            self.lower[0]
+        } else if y >= self.upper[1] {
+            self.upper[0]
+        } else {
+            let t = (y - self.lower[1]) / (self.upper[1] - self.lower[1]);
+            lerp(self.lower[0], self.upper[0], t)
+        }
+    }
+}
+
+fn split_edges(rings: &[Vec<[f32; 2]>]) -> Vec<Edge> {
+    let mut segments = Vec::new();
+    for ring in rings {
+        // Dropping non-finite points keeps the rest of the ring closed.
+        let ring: Vec<[f32; 2]> = ring
+            .iter()
+            .copied()
+            .filter(|point| point.iter().all(|c| c.is_finite()))
+            .collect();
+
+        for (i, &start) in ring.iter().enumerate() {
+            let end = ring[(i + 1) % ring.len()];
+            if start != end {
+                segments.push((
+                    [start[0] as f64, start[1] as f64],
+                    [end[0] as f64, end[1] as f64],
+                ));
+            }
+        }
+    }
+
+    let mut splits = vec![Vec::new(); segments.len()];
+    find_intersections(&segments, &mut splits);
+
+    let mut edges = Vec::new();
+    for ((start, end), mut points) in segments.into_iter().zip(splits) {
+        let direction = sub(end, start);
+        let param = |point: &Point| {
+            sub(*point, start)[0] * direction[0] + sub(*point, start)[1] * direction[1]
+        };
+        points.sort_by(|a, b| param(a).total_cmp(&param(b)));
+        points.insert(0, start);
+        points.push(end);
+
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if a[1] < b[1] {
+                edges.push(Edge {
+                    lower: a,
+                    upper: b,
+                    winding: 1,
+                });
+            } else if a[1] > b[1] {
+                edges.push(Edge {
+                    lower: b,
+                    upper: a,
+                    winding: -1,
+                });
+            }
+        }
+    }
+
+    edges
+}
+
+/// A filled span within a slab, as the x coordinates of its left and right
+/// sides at the bottom and top of the slab.
+#[derive(Copy, Clone)]
+struct Span {
+    left: (f64, f64),
+    right: (f64, f64),
+}
+
+struct Slab {
+    bottom: f64,
+    top: f64,
+    spans: Vec<Span>,
+}
+
+impl Slab {
+    fn bottom_intervals(&self) -> Vec<(f64, f64)> {
+        self.spans.iter().map(|s| (s.left.0, s.right.0)).collect()
+    }
+
+    fn top_intervals(&self) -> Vec<(f64, f64)> {
+        self.spans.iter().map(|s| (s.left.1, s.right.1)).collect()
+    }
+}
+
+fn slabs(mut edges: Vec<Edge>, rule: FillRule) -> Vec<Slab> {
+    let mut ys: Vec<f64> = edges
+        .iter()
+        .flat_map(|edge| vec![edge.lower[1], edge.upper[1]])
+        .collect();
+    ys.sort_by(f64::total_cmp);
+    ys.dedup();
+
+    edges.sort_by(|a, b| a.lower[1].total_cmp(&b.lower[1]));
+
+    let mut slabs = Vec::new();
+    let mut next_edge = 0;
+    let mut active: Vec<&Edge> = Vec::new();
+
+    for pair in ys.windows(2) {
+        let (bottom, top) = (pair[0], pair[1]);
+
+        while next_edge < edges.len() && edges[next_edge].lower[1] <= bottom {
+            active.push(&edges[next_edge]);
+            next_edge += 1;
+        }
+        active.retain(|edge| edge.upper[1] >= top);
+
+        let middle = (bottom + top) / 2.;
+        active.sort_by(|a, b| a.x_at(middle).total_cmp(&b.x_at(middle)));
+
+        let mut spans = Vec::new();
+        let mut winding = 0;
+        let mut left = None;
+        for edge in &active {
+            let was_inside = rule.is_inside(winding);
+            winding += edge.winding;
+            let is_inside = rule.is_inside(winding);
+
+            let x = (edge.x_at(bottom), edge.x_at(top));
+            if !was_inside && is_inside {
+                left = Some(x);
+            } else if was_inside && !is_inside {
+                if let Some(left) = left.take() {
+                    spans.push(Span { left, right: x });
+                }
+            }
+        }
+
+        slabs.push(Slab { bottom, top, spans });
+    }
+
+    slabs
+}
+
+fn contains(intervals: &[(f64, f64)], x: f64) -> bool {
+    intervals.iter().any(|&(a, b)| a <= x && x <= b)
+}
+
+/// Returns the parts of the line covered by exactly one of `a` and `b`,
+/// which is where the fill starts or stops across a slab boundary.
+fn symmetric_difference(a: &[(f64, f64)], b: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut breaks: Vec<f64> = a.iter().chain(b).flat_map(|&(s, e)| vec![s, e]).collect();
+    breaks.sort_by(f64::total_cmp);
+    breaks.dedup();
+
+    let mut result: Vec<(f64, f64)> = Vec::new();
+    for pair in breaks.windows(2) {
+        let middle = (pair[0] + pair[1]) / 2.;
+        if contains(a, middle) != contains(b, middle) {
+            match result.last_mut() {
+                Some(last) if last.1 == pair[0] => last.1 = pair[1],
+                _ => result.push((pair[0], pair[1])),
+            }
+        }
+    }
+    result
+}
+
+/// Fractions along `(start, end)` at which an interval of `outline` starts
+/// or ends.
+fn break_fractions(start: f64, end: f64, outline: &[(f64, f64)], fractions: &mut Vec<f64>) {
+    if end - start <= EPSILON {
+        return;
+    }
+    for &(a, b) in outline {
+        for &x in &[a, b] {
+            if x > start && x < end {
+                fractions.push((x - start) / (end - start));
+            }
+        }
+    }
+}
+
+/// Signed distance from `point` to the line through `a` and `b`, positive on
+/// the side of `inside`.
+fn distance(point: Point, a: Point, b: Point, inside: Point) -> f32 {
+    let direction = sub(b, a);
+    let length = (direction[0] * direction[0] + direction[1] * direction[1]).sqrt();
+    if length <= EPSILON {
+        return FAR;
+    }
+    let side = |p: Point| cross(direction, sub(p, a)) / length;
+    let d = side(point);
+    (if side(inside) < 0. { -d } else { d }) as f32
+}
+
+fn emit_piece(corners: [Point; 4], outline: [bool; 4], color: Color, out: &mut Vec<FillVertex>) {
+    let [bottom_left, bottom_right, top_left, top_right] = corners;
+    let center = [
+        corners.iter().map(|c| c[0]).sum::<f64>() / 4.,
+        corners.iter().map(|c| c[1]).sum::<f64>() / 4.,
+    ];
+
+    let vertex = |point: Point| {
+        let sides = [
+            distance(point, bottom_left, top_left, center),
+            distance(point, bottom_right, top_right, center),
+            (top_left[1] - point[1]) as f32,
+            (point[1] - bottom_left[1]) as f32,
+        ];
+        let mut edges = [FAR; 4];
+        for i in 0..4 {
+            if outline[i] {
+                edges[i] = sides[i];
+            }
+        }
+
+        FillVertex {
+            position: [point[0] as f32, point[1] as f32],
+            edges,
+            color,
+        }
+    };
+
+    for &corner in &[
+        bottom_left,
+        bottom_right,
+        top_left,
+        bottom_right,
+        top_right,
+        top_left,
+    ] {
+        out.push(vertex(corner));
+    }
+}
+
+pub(crate) fn tessellate(
+    rings: &[Vec<[f32; 2]>],
+    rule: FillRule,
+    color: Color,
+    out: &mut Vec<FillVertex>,
+) {
+    let slabs = slabs(split_edges(rings), rule);
+
+    for (i, slab) in slabs.iter().enumerate() {
+        let below = if i > 0 {
+            slabs[i - 1].top_intervals()
+        } else {
+            Vec::new()
+        };
+        let above = slabs
+            .get(i + 1)
+            .map(|slab| slab.bottom_intervals())
+            .unwrap_or_default();
+
+        let bottom_outline = symmetric_difference(&below, &slab.bottom_intervals());
+        let top_outline = symmetric_difference(&slab.top_intervals(), &above);
+
+        for span in &slab.spans {
+            let (b0, b1) = (span.left.0, span.right.0);
+            let (t0, t1) = (span.left.1, span.right.1);
+
+            let mut fractions = vec![0., 1.];
+            break_fractions(b0, b1, &bottom_outline, &mut fractions);
+            break_fractions(t0, t1, &top_outline, &mut fractions);
+            fractions.sort_by(f64::total_cmp);
+            fractions.dedup();
+
+            for pair in fractions.windows(2) {
+                let (fa, fb) = (pair[0], pair[1]);
+                let bottom = (lerp(b0, b1, fa), lerp(b0, b1, fb));
+                let top = (lerp(t0, t1, fa), lerp(t0, t1, fb));
+
+                let bottom_on_outline = bottom.1 - bottom.0 > EPSILON
+                    && contains(&bottom_outline, (bottom.0 + bottom.1) / 2.);
+                let top_on_outline =
+                    top.1 - top.0 > EPSILON && contains(&top_outline, (top.0 + top.1) / 2.);
+
+                emit_piece(
+                    [
+                        [bottom.0, slab.bottom],
+                        [bottom.1, slab.bottom],
+                        [top.0, slab.top],
+                        [top.1, slab.top],
+                    ],
+                    [fa == 0., fb == 1., top_on_outline, bottom_on_outline],
+                    color,
+                    out,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(rings: &[Vec<[f32; 2]>], rule: FillRule) -> f64 {
+        let mut vertices = Vec::new();
+        tessellate(rings, rule, Color::WHITE, &mut vertices);
+
+        vertices
+            .chunks(3)
+            .map(|t| {
+                let [a, b, c] = [t[0].position, t[1].position, t[2].position];
+                let ab = [(b[0] - a[0]) as f64, (b[1] - a[1]) as f64];
+                let ac = [(c[0] - a[0]) as f64, (c[1] - a[1]) as f64];
+                cross(ab, ac).abs() / 2.
+            })
+            .sum()
+    }
+
+    fn assert_area(rings: &[Vec<[f32; 2]>], rule: FillRule, expected: f64) {
+        let actual = area(rings, rule);
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{:?} area is {}, expected {}",
+            rule,
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn square() {
+        let rings = vec![vec![[0., 0.], [10., 0.], [10., 10.], [0., 10.]]];
+
+        assert_area(&rings, FillRule::EvenOdd, 100.);
+        assert_area(&rings, FillRule::NonZero, 100.);
+    }
+
+    #[test]
+    fn square_with_hole() {
+        let rings = vec![
+            vec![[0., 0.], [10., 0.], [10., 10.], [0., 10.]],
+            vec![[3., 3.], [3., 7.], [7., 7.], [7., 3.]],
+        ];
+
+        assert_area(&rings, FillRule::EvenOdd, 84.);
+        assert_area(&rings, FillRule::NonZero, 84.);
+    }
+
+    #[test]
+    fn bow_tie() {
+        let rings = vec![vec![[0., 0.], [10., 10.], [10., 0.], [0., 10.]]];
+
+        assert_area(&rings, FillRule::EvenOdd, 50.);
+        assert_area(&rings, FillRule::NonZero, 50.);
+    }
+
+    #[test]
+    fn pentagram() {
+        use std::f64::consts::PI;
+
+        let outer = 10.;
+        let points: Vec<[f32; 2]> = (0..5)
+            .map(|i| {
+                let angle = PI / 2. + 4. * PI * i as f64 / 5.;
+                [(outer * angle.cos()) as f32, (outer * angle.sin()) as f32]
+            })
+            .collect();
+
+        // The star is ten triangles between the center, its tips and the
+        // corners of the pentagon in its middle, which only NonZero fills.
+        let inner = outer * (2. * PI / 5.).cos() / (PI / 5.).cos();
+        let star = 5. * outer * inner * (PI / 5.).sin();
+        let pentagon = 2.5 * inner * inner * (2. * PI / 5.).sin();
+
+        assert_area(
+            std::slice::from_ref(&points),
+            FillRule::EvenOdd,
+            star - pentagon,
+        );
+        assert_area(&[points], FillRule::NonZero, star);
+    }
+
+    #[test]
+    fn non_finite_points_are_skipped() {
+        let rings = vec![vec![
+            [0., 0.],
+            [10., 0.],
+            [f32::NAN, 5.],
+            [10., 10.],
+            [f32::INFINITY, 10.],
+            [0., 10.],
+        ]];
+
+        assert_area(&rings, FillRule::NonZero, 100.);
+    }
+}
@@ -0,0 +1,527 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::dash::{DashBinding, DashPattern};
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use cgmath::{InnerSpace, Vector2};
+use std::borrow::Cow;
+use std::f32::consts::PI;
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+/// Segments closer to parallel than this are joined without a join shape.
+const COLLINEAR_EPSILON: f32 = 1e-6;
+
+/// Maximum angle, in radians, between two points on the rim of a round join.
+const ROUND_JOIN_STEP: f32 = PI / 16.;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LineJoin {
+    /// Extends the outer edges until they meet, falling back to a bevel when
+    /// the miter would reach further than `limit` times `width` from the point.
+    Miter {
+        limit: f32,
+    },
+    Round,
+    Bevel,
+}
+
+impl Default for LineJoin {
+    fn default() -> Self {
+        LineJoin::Miter { limit: 4. }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Polyline {
+    pub points: Vec<[f32; 2]>,
+    pub color: Color,
+    /// Like `Line::width`, the stroke extends `width` to each side of the
+    /// points.
+    pub width: f32,
+    pub join: LineJoin,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+pub(crate) struct StrokeVertex {
+    position: [f32; 2],
+    side: f32,
+    /// Distance along the polyline from its first point, for dashing.
+    distance: f32,
+    color: Color,
+}
+
+impl GpuSerializable for StrokeVertex {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<StrokeVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 3]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+            ],
+        }
+    }
+}
+
+/// Triangulates strokes. Every vertex carries a `side` value that is -1 and
+/// 1 on the two edges of the stroke and 0 on the center line, which the
+/// fragment shader uses for anti-aliasing.
+struct Stroker<'a> {
+    color: Color,
+    /// Distance along the polyline given to vertices of joins.
+    distance: f32,
+    out: &'a mut Vec<StrokeVertex>,
+}
+
+impl<'a> Stroker<'a> {
+    fn vertex(&mut self, position: Vector2<f32>, side: f32, distance: f32) {
+        self.out.push(StrokeVertex {
+            position: position.into(),
+            side,
+            distance,
+            color: self.color,
+        });
+    }
+
+    fn triangle(&mut self, vertices: [(Vector2<f32>, f32); 3]) {
+        for &(position, side) in &vertices {
+            self.vertex(position, side, self.distance);
+        }
+    }
+
+    /// Adds a quad between a start edge and an end edge, each given as its
+    /// (left, right) corners, which are `start_distance` and `end_distance`
+    /// along the polyline.
+    fn quad(&mut self, start: Edge, end: Edge, start_distance: f32, end_distance: f32) {
+        self.vertex(start.0, 1., start_distance);
+        self.vertex(start.1, -1., start_distance);
+        self.vertex(end.0, 1., end_distance);
+        self.vertex(start.1, -1., start_distance);
+        self.vertex(end.1, -1., end_distance);
+        self.vertex(end.0, 1., end_distance);
+    }
+
+    /// Adds triangles from `center` to each consecutive pair of `rim` points,
+    /// which lie on the edge of the stroke on side `side`.
+    fn fan(&mut self, center: Vector2<f32>, rim: &[Vector2<f32>], side: f32) {
+        for pair in rim.windows(2) {
+            self.triangle([(center, 0.), (pair[0], side), (pair[1], side)]);
+        }
+    }
+}
+
+fn normal(direction: Vector2<f32>) -> Vector2<f32> {
+    Vector2::new(-direction.y, direction.x)
+}
+
+/// Points on an arc of radius `radius` around `center`, from `from` to `to`
+/// (both offsets from `center`), going the short way around.
+fn arc(
+    center: Vector2<f32>,
+    from: Vector2<f32>,
+    to: Vector2<f32>,
+    radius: f32,
+) -> Vec<Vector2<f32>> {
+    let start = from.y.atan2(from.x);
+    let mut sweep = to.y.atan2(to.x) - start;
+    if sweep > PI {
+        sweep -= 2. * PI;
+    } else if sweep < -PI {
+        sweep += 2. * PI;
+    }
+
+    let steps = (sweep.abs() / ROUND_JOIN_STEP).ceil().max(1.) as usize;
+    (0..=steps)
+        .map(|i| {
+            let theta = start + sweep * i as f32 / steps as f32;
+            center + radius * Vector2::new(theta.cos(), theta.sin())
+        })
+        .collect()
+}
+
+/// The corners a segment starts or ends with at a point, as (left, right).
+type Edge = (Vector2<f32>, Vector2<f32>);
+
+pub(crate) fn stroke_polyline(
+    points: &[[f32; 2]],
+    width: f32,
+    join: LineJoin,
+    color: Color,
+    out: &mut Vec<StrokeVertex>,
+) {
+    let mut points: Vec<Vector2<f32>> = points.iter().map(|&p| Vector2::from(p)).collect();
+    points.dedup();
+    if points.len() < 2 {
+        return;
+    }
+
+    let directions: Vec<Vector2<f32>> = points
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).normalize())
+        .collect();
+    let lengths: Vec<f32> = points
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).magnitude())
+        .collect();
+
+    let mut stroker = Stroker {
+        color,
+        distance: 0.,
+        out,
+    };
+
+    let n0 = normal(directions[0]) * width;
+    let mut start: Edge = (points[0] + n0, points[0] - n0);
+
+    for i in 0..directions.len() {
+        let p = points[i + 1];
+        let d0 = directions[i];
+        let n0 = normal(d0) * width;
+        let start_distance = stroker.distance;
+        stroker.distance += lengths[i];
+        let end_distance = stroker.distance;
+
+        if i + 1 == directions.len() {
+            stroker.quad(start, (p + n0, p - n0), start_distance, end_distance);
+            break;
+        }
+
+        let d1 = directions[i + 1];
+        let n1 = normal(d1) * width;
+        let turn = d0.perp_dot(d1);
+
+        if turn.abs() < COLLINEAR_EPSILON && d0.dot(d1) > 0. {
+            stroker.quad(start, (p + n0, p - n0), start_distance, end_distance);
+            start = (p + n1, p - n1);
+            continue;
+        }
+
+        // The outer side of the turn is on the right for left turns.
+        let outer_side = if turn > 0. { -1. } else { 1. };
+        let outer_start = p + n0 * outer_side;
+        let outer_end = p + n1 * outer_side;
+
+        // On the inner side, both segments end at the point where their
+        // edges cross, so that they don't overlap. That point is only usable
+        // if it lies within both segments.
+        let inner = if turn.abs() < COLLINEAR_EPSILON {
+            None
+        } else {
+            let bisector = (n0 + n1).normalize() * -outer_side;
+            let miter_length = width / bisector.dot(normal(d0)).abs();
+            let inner = p + bisector * miter_length;
+            let reach = (inner - p).dot(d0).abs();
+            if reach <= lengths[i] && reach <= lengths[i + 1] {
+                Some(inner)
+            } else {
+                None
+            }
+        };
+
+        let end: Edge = match inner {
+            Some(inner) if outer_side < 0. => (inner, outer_start),
+            Some(inner) => (outer_start, inner),
+            None => (p + n0, p - n0),
+        };
+        stroker.quad(start, end, start_distance, end_distance);
+
+        let rim = match join {
+            LineJoin::Round => arc(p, outer_start - p, outer_end - p, width),
+            LineJoin::Miter { limit } if turn.abs() >= COLLINEAR_EPSILON => {
+                let bisector = (n0 + n1).normalize() * outer_side;
+                let miter_length = width / bisector.dot(n0 / width * outer_side);
+                if miter_length <= limit * width {
+                    vec![outer_start, p + bisector * miter_length, outer_end]
+                } else {
+                    vec![outer_start, outer_end]
+                }
+            }
+            _ => vec![outer_start, outer_end],
+        };
+        stroker.fan(p, &rim, outer_side);
+
+        if let Some(inner) = inner {
+            let inner_side = -outer_side;
+            stroker.triangle([(p, 0.), (inner, inner_side), (outer_start, outer_side)]);
+            stroker.triangle([(p, 0.), (outer_end, outer_side), (inner, inner_side)]);
+
+            start = if outer_side < 0. {
+                (inner, outer_end)
+            } else {
+                (outer_end, inner)
+            };
+        } else {
+            start = (p + n1, p - n1);
+        }
+    }
+}
+
+pub(crate) fn stroke_vertices(data: &[Polyline]) -> Vec<StrokeVertex> {
+    let mut vertices = Vec::new();
+    for polyline in data {
+        stroke_polyline(
+            &polyline.points,
+            polyline.width,
+            polyline.join,
+            polyline.color,
+            &mut vertices,
+        );
+    }
+    vertices
+}
+
+/// Polylines are tessellated on the CPU in scene coordinates, so their
+/// widths are always in scene units.
+pub struct PolylinesLayer {
+    data: Vec<Polyline>,
+    dash: Option<DashPattern>,
+}
+
+impl PolylinesLayer {
+    pub fn new(data: Vec<Polyline>) -> Self {
+        PolylinesLayer { data, dash: None }
+    }
+
+    /// Dashes every polyline in the layer. The pattern continues across
+    /// joins and restarts at each polyline's first point.
+    pub fn with_dash(mut self, dash: DashPattern) -> Self {
+        self.dash = Some(dash);
+        self
+    }
+}
+
+pub struct PolylinesLayerDrawable {
+    render_pipeline: RenderPipeline,
+    dash: DashBinding,
+    vertex_buffer: GpuBuffer<StrokeVertex>,
+}
+
+impl PolylinesLayerDrawable {
+    /// Replaces the polylines drawn by this drawable.
+    pub fn set_polylines(&mut self, data: &[Polyline], update_state: &UpdateState) {
+        self.vertex_buffer.update(
+            &stroke_vertices(data),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+    }
+}
+
+impl Drawable for PolylinesLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_bind_group(1, &self.dash.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.all());
+        render_pass.draw(0..self.vertex_buffer.len(), 0..1);
+    }
+}
+
+pub(crate) fn stroke_pipeline(draw_context: &DrawContext, dash: &DashBinding) -> RenderPipeline {
+    let DrawContext {
+        device,
+        sc_desc,
+        transform_layout,
+        sample_count,
+        ..
+    } = *draw_context;
+
+    let render_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some("Render Pipeline Layout"),
+        bind_group_layouts: &[transform_layout, &dash.layout],
+        push_constant_ranges: &[],
+    });
+
+    let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+        label: None,
+        source: ShaderSource::Wgsl(Cow::Borrowed(concat!(
+            include_str!("../dash.wgsl"),
+            include_str!("shader.wgsl")
+        ))),
+        flags: Default::default(),
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Render Pipeline"),
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader_module,
+            entry_point: "vs_main",
+            buffers: &[StrokeVertex::buffer_layout()],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader_module,
+            entry_point: "fs_main",
+            targets: &[wgpu::ColorTargetState {
+                format: sc_desc.format,
+                write_mask: wgpu::ColorWrite::ALL,
+                blend: Some(BlendState {
+                    color: BlendComponent::OVER,
+                    alpha: BlendComponent::REPLACE,
+                }),
+            }],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+            clamp_depth: false,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+    })
+}
+
+impl Layer for PolylinesLayer {
+    type D = PolylinesLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> PolylinesLayerDrawable {
+        let vertex_buffer = GpuBuffer::new(&stroke_vertices(&self.data), draw_context.device);
+        let dash = DashBinding::new(draw_context.device, self.dash.as_ref());
+
+        PolylinesLayerDrawable {
+            render_pipeline: stroke_pipeline(draw_context, &dash),
+            dash,
+            vertex_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.polylines(&self.data, self.dash.as_ref());
+    }
+}
+
+impl GenericLayer for PolylinesLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(points: &[[f32; 2]], join: LineJoin) -> Vec<StrokeVertex> {
+        let mut out = Vec::new();
+        stroke_polyline(points, 1., join, Color::WHITE, &mut out);
+        out
+    }
+
+    fn has_vertex(vertices: &[StrokeVertex], position: [f32; 2]) -> bool {
+        vertices.iter().any(|v| {
+            (v.position[0] - position[0]).abs() < 1e-5 && (v.position[1] - position[1]).abs() < 1e-5
+        })
+    }
+
+    const RIGHT_ANGLE: [[f32; 2]; 3] = [[0., 0.], [10., 0.], [10., 10.]];
+
+    #[test]
+    fn straight_run() {
+        let vertices = stroke(&[[0., 0.], [10., 0.], [20., 0.]], LineJoin::Bevel);
+
+        // One quad per segment, with no join between them.
+        assert_eq!(vertices.len(), 12);
+        for v in &vertices {
+            assert_eq!(v.position[1], v.side);
+            assert_eq!(v.distance, v.position[0]);
+        }
+    }
+
+    #[test]
+    fn right_angle_miter() {
+        let vertices = stroke(&RIGHT_ANGLE, LineJoin::Miter { limit: 4. });
+
+        // Two quads, the miter as two triangles and two triangles filling
+        // the inside of the turn.
+        assert_eq!(vertices.len(), 24);
+        assert!(has_vertex(&vertices, [11., -1.]));
+        assert!(has_vertex(&vertices, [9., 1.]));
+    }
+
+    #[test]
+    fn miter_limit_falls_back_to_bevel() {
+        // A right-angle miter reaches √2 times the width from the point.
+        let vertices = stroke(&RIGHT_ANGLE, LineJoin::Miter { limit: 1.4 });
+        let bevel = stroke(&RIGHT_ANGLE, LineJoin::Bevel);
+
+        assert_eq!(vertices.len(), 21);
+        assert!(!has_vertex(&vertices, [11., -1.]));
+        for (v, b) in vertices.iter().zip(&bevel) {
+            assert_eq!(v.position, b.position);
+        }
+    }
+
+    #[test]
+    fn round_join_fan() {
+        let vertices = stroke(&RIGHT_ANGLE, LineJoin::Round);
+
+        // A quarter turn is split into 8 steps of ROUND_JOIN_STEP.
+        assert_eq!(vertices.len(), 6 + 8 * 3 + 6 + 6);
+        let fan = &vertices[6..6 + 8 * 3];
+        for triangle in fan.chunks(3) {
+            assert_eq!(triangle[0].position, [10., 0.]);
+            assert_eq!(triangle[0].side, 0.);
+            for v in &triangle[1..] {
+                let offset = Vector2::from(v.position) - Vector2::new(10., 0.);
+                assert!((offset.magnitude() - 1.).abs() < 1e-5);
+                assert!(offset.x >= -1e-5 && offset.y <= 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_input() {
+        assert!(stroke(&[], LineJoin::Round).is_empty());
+        assert!(stroke(&[[1., 1.]], LineJoin::Round).is_empty());
+        assert!(stroke(&[[1., 1.], [1., 1.]], LineJoin::Round).is_empty());
+
+        let repeated = stroke(&[[0., 0.], [0., 0.], [10., 0.], [10., 0.]], LineJoin::Round);
+        let single = stroke(&[[0., 0.], [10., 0.]], LineJoin::Round);
+        assert_eq!(repeated.len(), 6);
+        for (r, s) in repeated.iter().zip(&single) {
+            assert_eq!(r.position, s.position);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    [[location(1)]] side: f32;
+    [[location(2)]] along: f32;
+    [[location(3)]] scene: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+[[stage(vertex)]]
+fn vs_main(
+    [[location(0)]] position: vec2<f32>,
+    [[location(1)]] side: f32,
+    [[location(2)]] color: vec4<f32>,
+    [[location(3)]] along: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.color = color;
+    out.side = side;
+    out.along = along;
+    out.scene = position;
+    out.position = uniforms.transform * vec4<f32>(position, 0., 1.);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // `side` is -1 and 1 on the two edges of the stroke and 0 along its center.
+    let side_alpha: f32 = clamp((1. - abs(in.side)) / fwidth(in.side), 0., 1.);
+
+    // Joins are drawn at a single distance, so measure pixels with the scene
+    // position rather than the distance along the polyline.
+    let scene_per_pixel: f32 = length(dpdx(in.scene));
+    let gap: f32 = dash_distance(in.along, scene_per_pixel);
+    let dash_alpha: f32 = clamp(0.5 - gap / scene_per_pixel, 0., 1.);
+
+    let alpha: f32 = min(side_alpha, dash_alpha);
+
+    return in.color * alpha;
+}
@@ -0,0 +1,71 @@
+use std::path::Path;
+use std::time::Duration;
+
+use winit::dpi::PhysicalSize;
+
+use crate::error::Error;
+use crate::headless::{Headless, Image};
+use crate::layer::Layer;
+use crate::zoom::Camera;
+
+/// Renders a fixed number of frames of a layer offscreen, advancing
+/// `UpdateState::elapsed` by exactly `1 / frame_rate` between frames, so
+/// that recordings are reproducible.
+pub struct Recording {
+    pub width: u32,
+    pub height: u32,
+    pub camera: Camera,
+    pub frames: u32,
+    pub frame_rate: f64,
+    pub sample_count: u32,
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Recording {
+            width: 600,
+            height: 600,
+            camera: Camera::default(),
+            frames: 60,
+            frame_rate: 30.,
+            sample_count: 1,
+        }
+    }
+}
+
+impl Recording {
+    pub fn frame_time(&self, frame: u32) -> Duration {
+        Duration::from_secs_f64(frame as f64 / self.frame_rate)
+    }
+
+    /// Calls `on_frame` with the index and pixels of each frame in order,
+    /// stopping at the first error.
+    pub fn render<T: Layer, E: From<Error>>(
+        &self,
+        layer: &T,
+        mut on_frame: impl FnMut(u32, Image) -> Result<(), E>,
+    ) -> Result<(), E> {
+        if !(self.frame_rate > 0. && self.frame_rate.is_finite()) {
+            return Err(Error::InvalidFrameRate(self.frame_rate).into());
+        }
+
+        let size = PhysicalSize::new(self.width, self.height);
+        let mut headless = Headless::new(layer, size, self.camera, self.sample_count)?;
+
+        for frame in 0..self.frames {
+            on_frame(frame, headless.render(self.frame_time(frame))?)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes each frame to `frame-00000.png`, `frame-00001.png`, ... in
+    /// `directory`, which must already exist.
+    pub fn save_pngs<T: Layer>(&self, layer: &T, directory: impl AsRef<Path>) -> Result<(), Error> {
+        let directory = directory.as_ref();
+
+        self.render(layer, |frame, image| {
+            image.save_png(directory.join(format!("frame-{:05}.png", frame)))
+        })
+    }
+}
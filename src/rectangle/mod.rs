@@ -2,19 +2,30 @@ use crate::layer::{DrawContext, DrawState, Drawable, Layer};
 use crate::{GenericDrawable, GenericLayer};
 
 use crate::color::Color;
+use crate::export::ShapeVisitor;
 use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use crate::units::WidthUnits;
 use std::borrow::Cow;
 use wgpu::{
     BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
     VertexBufferLayout,
 };
+use winit::dpi::PhysicalSize;
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Zeroable, bytemuck::Pod)]
 pub struct Rectangle {
     pub upper_left: [f32; 2],
     pub bottom_right: [f32; 2],
     pub color: Color,
+    /// Radius of the rounded corners, limited to half the shorter side.
+    pub corner_radius: f32,
+    /// Counter-clockwise rotation around the center, in radians.
+    pub rotation: f32,
+    /// Like `Line::width`, the stroke extends `stroke_width` to each side of
+    /// the rectangle's edge. The fill is only drawn inside the stroke.
+    pub stroke_width: f32,
+    pub stroke_color: Color,
 }
 
 impl GpuSerializable for Rectangle {
@@ -42,6 +53,26 @@ impl GpuSerializable for Rectangle {
                     shader_location: 2,
                     format: wgpu::VertexFormat::Unorm8x4,
                 },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 7]>() as wgpu::BufferAddress,
+                    shader_location: 5,
+                    format: wgpu::VertexFormat::Float32,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 6,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
             ],
         }
     }
@@ -49,11 +80,21 @@ impl GpuSerializable for Rectangle {
 
 pub struct RectanglesLayer {
     data: Vec<Rectangle>,
+    units: WidthUnits,
 }
 
 impl RectanglesLayer {
     pub fn new(data: Vec<Rectangle>) -> Self {
-        RectanglesLayer { data }
+        RectanglesLayer {
+            data,
+            units: WidthUnits::default(),
+        }
+    }
+
+    /// Sets the units of the stroke widths of every rectangle in the layer.
+    pub fn with_width_units(mut self, units: WidthUnits) -> Self {
+        self.units = units;
+        self
     }
 }
 
@@ -63,7 +104,7 @@ pub struct RectanglesLayerDrawable {
 }
 
 impl Drawable for RectanglesLayerDrawable {
-    fn draw<'a>(&'a self, draw_state: &DrawState<'a>) {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
         let mut render_pass = draw_state.render_pass.borrow_mut();
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, draw_state.bind_group, &[]);
@@ -80,6 +121,8 @@ impl Layer for RectanglesLayer {
             device,
             sc_desc,
             transform_layout,
+            sample_count,
+            ..
         } = *draw_context;
         let instance_buffer = GpuBuffer::new(&self.data, device);
 
@@ -92,7 +135,9 @@ impl Layer for RectanglesLayer {
 
         let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
             label: None,
-            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            source: ShaderSource::Wgsl(Cow::Owned(
+                self.units.shader_source(include_str!("shader.wgsl")),
+            )),
             flags: Default::default(),
         });
 
@@ -129,7 +174,7 @@ impl Layer for RectanglesLayer {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -140,10 +185,18 @@ impl Layer for RectanglesLayer {
             instance_buffer,
         }
     }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.rectangles(&self.data, self.units);
+    }
 }
 
 impl GenericLayer for RectanglesLayer {
     fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
         GenericDrawable::new(self.init_drawable(draw_context))
     }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
 }
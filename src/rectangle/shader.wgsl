@@ -1,51 +1,94 @@
 struct VertexOutput {
     [[location(0)]] color: vec4<f32>;
+    [[location(1)]] stroke_color: vec4<f32>;
+    // Offset from the center of the rectangle along its own axes, in scene
+    // units.
+    [[location(2)]] coord: vec2<f32>;
+    // Half the width and height of the rectangle.
+    [[location(3)]] half_size: vec2<f32>;
+    // (corner radius, stroke width) of the rectangle.
+    [[location(4)]] shape: vec2<f32>;
     [[builtin(position)]] position: vec4<f32>;
 };
 
-[[block]]
-struct Uniforms {
-    transform: mat4x4<f32>;
-};
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(-1., -1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., -1.),
+    vec2<f32>(-1., 1.),
+    vec2<f32>(1., 1.),
+);
+
+fn vertex(in_vertex_index: u32, upper_left: vec2<f32>, lower_right: vec2<f32>,
+    color: vec4<f32>, corner_radius: f32, rotation: f32, stroke_width: f32,
+    stroke_color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
 
-[[group(0), binding(0)]]
-var uniforms: Uniforms;
+    let center: vec2<f32> = (upper_left + lower_right) / 2.;
+    let half_size: vec2<f32> = abs(lower_right - upper_left) / 2.;
+
+    // Leave room for the outer half of the stroke.
+    let local: vec2<f32> = corners[in_vertex_index] * (half_size + max(stroke_width, 0.));
+    let c: f32 = cos(rotation);
+    let s: f32 = sin(rotation);
+    let offset: vec2<f32> = vec2<f32>(c * local.x - s * local.y, s * local.x + c * local.y);
+
+    out.color = color;
+    out.stroke_color = stroke_color;
+    out.coord = local;
+    out.half_size = half_size;
+    out.shape = vec2<f32>(
+        clamp(corner_radius, 0., min(half_size.x, half_size.y)),
+        stroke_width,
+    );
+    out.position = uniforms.transform * vec4<f32>(center + offset, 0., 1.);
+
+    return out;
+}
 
 [[stage(vertex)]]
 fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
     [[location(0)]] upper_left: vec2<f32>,
     [[location(1)]] lower_right: vec2<f32>,
     [[location(2)]] color: vec4<f32>,
+    [[location(3)]] corner_radius: f32,
+    [[location(4)]] rotation: f32,
+    [[location(5)]] stroke_width: f32,
+    [[location(6)]] stroke_color: vec4<f32>,
 ) -> VertexOutput {
-    var out: VertexOutput;
+    return vertex(in_vertex_index, upper_left, lower_right, color, corner_radius, rotation,
+        stroke_width * width_scale(), stroke_color);
+}
 
-    switch (i32(in_vertex_index)) {
-        case 0: {
-            out.position = vec4<f32>(upper_left, 0., 1.); 
-        }
-        case 1: {
-            fallthrough;
-        }
-        case 3: {
-            out.position = vec4<f32>(upper_left.x, lower_right.y, 0., 1.);
-        }
-        case 2: {
-            fallthrough;
-        }
-        case 4: {
-            out.position = vec4<f32>(lower_right.x, upper_left.y, 0., 1.);
-        }
-        case 5: {
-            out.position = vec4<f32>(lower_right, 0., 1.);
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let radius: f32 = in.shape.x;
+    let stroke_width: f32 = in.shape.y;
+
+    // Signed distance to the edge of the rounded rectangle, negative inside.
+    let q: vec2<f32> = abs(in.coord) - in.half_size + radius;
+    let d: f32 = length(max(q, vec2<f32>(0., 0.))) + min(max(q.x, q.y), 0.) - radius;
+    let delta: f32 = fwidth(d);
+
+    if (stroke_width <= 0.) {
+        let alpha: f32 = clamp(0.5 - d / delta, 0., 1.);
+        if (alpha < 0.01) {
+            discard;
         }
+
+        return in.color * alpha;
     }
 
-    out.position = uniforms.transform * out.position;
-    out.color = color;
-    return out;
-}
+    // As with circles, the stroke covers whatever is inside its outer edge
+    // but not inside its inner edge, where the fill is drawn instead.
+    let outer: f32 = clamp(0.5 - (d - stroke_width) / delta, 0., 1.);
+    let inner: f32 = clamp(0.5 - (d + stroke_width) / delta, 0., 1.);
 
-[[stage(fragment)]]
-fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
-    return in.color;
+    if (outer < 0.01) {
+        discard;
+    }
+
+    return in.stroke_color * (outer - inner) + in.color * inner;
 }
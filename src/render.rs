@@ -0,0 +1,377 @@
+use std::cell::RefCell;
+use std::time::Duration;
+
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsage, CommandEncoder,
+    Device, Instance, PowerPreference, Queue, RenderPass, ShaderStage, Surface,
+    SwapChainDescriptor, Texture, TextureFormat, TextureView,
+};
+use winit::dpi::PhysicalSize;
+
+use crate::error::Error;
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::zoom::{Mat4, IDENTITY};
+
+pub const CLEAR_COLOR: wgpu::Color = wgpu::Color::WHITE;
+
+pub async fn request_device(
+    instance: &Instance,
+    surface: Option<&Surface>,
+    power_preference: PowerPreference,
+) -> Result<(Device, Queue), Error> {
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference,
+            compatible_surface: surface,
+        })
+        .await
+        .ok_or(Error::NoAdapter)?;
+
+    let device = adapter
+        .request_device(
+            &wgpu::DeviceDescriptor {
+                label: None,
+                features: wgpu::Features::empty(),
+                limits: wgpu::Limits::default(),
+            },
+            None, // Trace path
+        )
+        .await?;
+
+    Ok(device)
+}
+
+pub struct ColorTarget<'a> {
+    pub view: &'a TextureView,
+    pub resolve_target: Option<&'a TextureView>,
+}
+
+/// The multisampled texture that frames are drawn into before being resolved
+/// to the output, when the sample count is greater than one.
+pub struct Multisample {
+    texture: Option<(Texture, TextureView)>,
+}
+
+impl Multisample {
+    pub fn new(
+        device: &Device,
+        size: PhysicalSize<u32>,
+        format: TextureFormat,
+        sample_count: u32,
+    ) -> Self {
+        if sample_count <= 1 {
+            return Multisample { texture: None };
+        }
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Multisampled frame"),
+            size: wgpu::Extent3d {
+                width: size.width,
+                height: size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Multisample {
+            texture: Some((texture, view)),
+        }
+    }
+
+    pub fn target<'a>(&'a self, output: &'a TextureView) -> ColorTarget<'a> {
+        match &self.texture {
+            Some((_, view)) => ColorTarget {
+                view,
+                resolve_target: Some(output),
+            },
+            None => ColorTarget {
+                view: output,
+                resolve_target: None,
+            },
+        }
+    }
+}
+
+/// Contents of the uniform buffer bound at group 0. Shaders that only need
+/// the transform can declare just that first field.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+struct TransformUniform {
+    transform: Mat4,
+    /// Size of the render target in pixels.
+    viewport: [f32; 2],
+    _padding: [f32; 2],
+}
+
+impl TransformUniform {
+    fn new(transform: Mat4, size: PhysicalSize<u32>) -> Self {
+        TransformUniform {
+            transform,
+            viewport: [size.width as f32, size.height as f32],
+            _padding: [0., 0.],
+        }
+    }
+}
+
+struct Transform {
+    buffer: Buffer,
+    layout: BindGroupLayout,
+    bind_group: BindGroup,
+}
+
+impl Transform {
+    fn new(device: &Device, transform: Mat4) -> Self {
+        let uniform = TransformUniform::new(transform, PhysicalSize::new(1, 1));
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Transformation buffer"),
+            contents: bytemuck::cast_slice(&[uniform]),
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+        });
+
+        let layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Transformation bind group layout"),
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStage::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Transformation bind group"),
+            layout: &layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            }],
+        });
+
+        Transform {
+            buffer,
+            layout,
+            bind_group,
+        }
+    }
+
+    fn update(
+        &self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        transform: Mat4,
+        size: PhysicalSize<u32>,
+    ) {
+        let tmp_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Temporary Buffer"),
+            contents: bytemuck::cast_slice(&[TransformUniform::new(transform, size)]),
+            usage: BufferUsage::COPY_SRC,
+        });
+
+        encoder.copy_buffer_to_buffer(
+            &tmp_buffer,
+            0,
+            &self.buffer,
+            0,
+            std::mem::size_of::<TransformUniform>() as u64,
+        );
+    }
+}
+
+/// What a frame is rendered with, besides the drawable and its target.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameState {
+    /// The transform from scene coordinates to clip space (see
+    /// `Camera::matrix`).
+    pub transform: Mat4,
+    /// Size of the render target in pixels.
+    pub size: PhysicalSize<u32>,
+    /// Time since the first frame.
+    pub elapsed: Duration,
+}
+
+/// Draws layers using a device, command encoder and render pass owned by
+/// the caller, for embedding vecdraw in an existing wgpu application.
+///
+/// Each frame, call `update` on an encoder before beginning the render pass,
+/// then `draw` inside it. The render pass must target a texture with the
+/// format and sample count the renderer was created with.
+///
+/// ```
+/// # use vecdraw::{Drawable, FrameState, Renderer};
+/// fn frame<D: Drawable>(
+///     device: &wgpu::Device,
+///     renderer: &Renderer,
+///     drawable: &mut D,
+///     frame: FrameState,
+///     view: &wgpu::TextureView,
+/// ) -> wgpu::CommandBuffer {
+///     let encoder = device.create_command_encoder(&Default::default());
+///     let mut encoder = renderer.update(device, encoder, frame, drawable);
+///     {
+///         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+///             label: None,
+///             color_attachments: &[wgpu::RenderPassColorAttachment {
+///                 view,
+///                 resolve_target: None,
+///                 ops: wgpu::Operations::default(),
+///             }],
+///             depth_stencil_attachment: None,
+///         });
+///         // The application's own draw calls can go before or after.
+///         renderer.draw(&mut render_pass, drawable);
+///     }
+///     encoder.finish()
+/// }
+/// ```
+pub struct Renderer {
+    transform: Transform,
+    format: TextureFormat,
+    sample_count: u32,
+}
+
+impl Renderer {
+    pub fn new(device: &Device, format: TextureFormat, sample_count: u32) -> Self {
+        Renderer {
+            transform: Transform::new(device, IDENTITY),
+            format,
+            sample_count,
+        }
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Creates the drawable for `layer`, for a target of the given size.
+    pub fn init_drawable<T: Layer>(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        layer: &T,
+        size: PhysicalSize<u32>,
+    ) -> T::D {
+        let sc_desc = SwapChainDescriptor {
+            usage: wgpu::TextureUsage::RENDER_ATTACHMENT,
+            format: self.format,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+
+        layer.init_drawable(&DrawContext {
+            device,
+            queue,
+            sc_desc: &sc_desc,
+            transform_layout: &self.transform.layout,
+            sample_count: self.sample_count,
+        })
+    }
+
+    /// Records the upload of the frame's transform and the drawable's update
+    /// step.
+    pub fn update<D: Drawable>(
+        &self,
+        device: &Device,
+        encoder: CommandEncoder,
+        frame: FrameState,
+        drawable: &mut D,
+    ) -> CommandEncoder {
+        let FrameState {
+            transform,
+            size,
+            elapsed,
+        } = frame;
+
+        let mut encoder = encoder;
+        self.transform.update(device, &mut encoder, transform, size);
+
+        let encoder = RefCell::new(encoder);
+
+        {
+            let update_state = UpdateState {
+                encoder: &encoder,
+                device,
+                elapsed,
+                transform,
+                size,
+            };
+            drawable.update(&update_state);
+        }
+
+        encoder.into_inner()
+    }
+
+    /// Records the draw calls for `drawable` into `render_pass`, which can
+    /// be used for more draw calls afterwards.
+    pub fn draw<'a, D: Drawable>(&'a self, render_pass: &mut RenderPass<'a>, drawable: &'a D) {
+        let draw_state = DrawState {
+            render_pass: RefCell::new(render_pass),
+            bind_group: &self.transform.bind_group,
+        };
+
+        drawable.draw(&draw_state);
+    }
+}
+
+/// Records a full frame of `drawable` into `target`: the transform upload,
+/// the drawable's update step, and a render pass that clears to `clear_color`.
+pub fn encode_frame<D: Drawable>(
+    device: &Device,
+    renderer: &Renderer,
+    frame: FrameState,
+    drawable: &mut D,
+    target: ColorTarget,
+    clear_color: wgpu::Color,
+) -> CommandEncoder {
+    let encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Render Encoder"),
+    });
+
+    let encoder = renderer.update(device, encoder, frame, drawable);
+    encode_draw(encoder, renderer, drawable, target, clear_color)
+}
+
+/// Records a render pass of `drawable` into `target` that clears to
+/// `clear_color`, without updating the transform or the drawable, so that it
+/// shows the same frame as the last one encoded.
+pub fn encode_draw<D: Drawable>(
+    mut encoder: CommandEncoder,
+    renderer: &Renderer,
+    drawable: &D,
+    target: ColorTarget,
+    clear_color: wgpu::Color,
+) -> CommandEncoder {
+    {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: target.view,
+                resolve_target: target.resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(clear_color),
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+
+        renderer.draw(&mut render_pass, drawable);
+    }
+
+    encoder
+}
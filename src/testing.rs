@@ -0,0 +1,193 @@
+//! Golden-image helpers for testing how layers render.
+//!
+//! A test renders a layer offscreen and compares it to a reference PNG
+//! stored alongside the tests. Reference images are recorded by running the
+//! tests with `VECDRAW_BLESS=1` set. Without a graphics adapter, a test
+//! fails unless `VECDRAW_SKIP_GPU=1` is set, in which case it is skipped.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use winit::dpi::PhysicalSize;
+
+use crate::error::Error;
+use crate::headless::{Headless, Image};
+use crate::layer::Layer;
+use crate::zoom::Camera;
+
+const BLESS_VAR: &str = "VECDRAW_BLESS";
+const SKIP_GPU_VAR: &str = "VECDRAW_SKIP_GPU";
+
+pub struct GoldenTest {
+    pub width: u32,
+    pub height: u32,
+    pub camera: Camera,
+    pub sample_count: u32,
+    /// The largest difference allowed in any channel of a pixel.
+    pub tolerance: u8,
+}
+
+impl Default for GoldenTest {
+    fn default() -> Self {
+        GoldenTest {
+            width: 256,
+            height: 256,
+            camera: Camera::default(),
+            sample_count: 1,
+            tolerance: 2,
+        }
+    }
+}
+
+/// Compares two images, returning `None` if every channel of every pixel is
+/// within `tolerance`. Otherwise returns the number of mismatched pixels and
+/// an image with mismatches in red over a faded copy of `expected`.
+pub fn diff_images(actual: &Image, expected: &Image, tolerance: u8) -> Option<(usize, Image)> {
+    assert_eq!(
+        (actual.width, actual.height),
+        (expected.width, expected.height),
+        "Image sizes differ"
+    );
+
+    let mut mismatched = 0;
+    let mut data = Vec::with_capacity(expected.data.len());
+    for (a, e) in actual.data.chunks(4).zip(expected.data.chunks(4)) {
+        let matches = a
+            .iter()
+            .zip(e)
+            .all(|(a, e)| (*a as i16 - *e as i16).abs() <= tolerance as i16);
+
+        if matches {
+            let luma = (e[0] as u32 + e[1] as u32 + e[2] as u32) / 3;
+            let faded = (0xc0 + luma / 4) as u8;
+            data.extend_from_slice(&[faded, faded, faded, 0xff]);
+        } else {
+            mismatched += 1;
+            data.extend_from_slice(&[0xff, 0, 0, 0xff]);
+        }
+    }
+
+    if mismatched == 0 {
+        return None;
+    }
+
+    let diff = Image {
+        width: expected.width,
+        height: expected.height,
+        data,
+    };
+    Some((mismatched, diff))
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{}.{}.png", stem, suffix))
+}
+
+impl GoldenTest {
+    /// Renders `layer` and panics if it doesn't match the reference image at
+    /// `reference`. On mismatch, `<name>.actual.png` and `<name>.diff.png`
+    /// are written next to the reference. With `VECDRAW_BLESS` set, the
+    /// rendered image replaces the reference instead.
+    pub fn assert_matches<T: Layer>(&self, layer: &T, reference: impl AsRef<Path>) {
+        let reference = reference.as_ref();
+        let size = PhysicalSize::new(self.width, self.height);
+        let actual = match Headless::new(layer, size, self.camera, self.sample_count)
+            .and_then(|mut headless| headless.render(Duration::default()))
+        {
+            Ok(actual) => actual,
+            Err(Error::NoAdapter) if std::env::var_os(SKIP_GPU_VAR).is_some() => {
+                eprintln!("skipping {}: no graphics adapter", reference.display());
+                return;
+            }
+            Err(Error::NoAdapter) => panic!(
+                "no graphics adapter to render {}; set {}=1 to skip GPU tests",
+                reference.display(),
+                SKIP_GPU_VAR
+            ),
+            Err(e) => panic!("failed to render {}: {}", reference.display(), e),
+        };
+
+        if std::env::var_os(BLESS_VAR).is_some() {
+            actual.save_png(reference).unwrap();
+            return;
+        }
+
+        if !reference.exists() {
+            let actual_path = sibling(reference, "actual");
+            actual.save_png(&actual_path).unwrap();
+
+            panic!(
+                "reference image {} is missing; check {} and rerun with {}=1 to record it",
+                reference.display(),
+                actual_path.display(),
+                BLESS_VAR
+            );
+        }
+
+        let expected = Image::load_png(reference).unwrap();
+
+        if let Some((mismatched, diff)) = diff_images(&actual, &expected, self.tolerance) {
+            let actual_path = sibling(reference, "actual");
+            let diff_path = sibling(reference, "diff");
+            actual.save_png(&actual_path).unwrap();
+            diff.save_png(&diff_path).unwrap();
+
+            panic!(
+                "{} pixels differ from {} by more than {}; see {} and {}",
+                mismatched,
+                reference.display(),
+                self.tolerance,
+                actual_path.display(),
+                diff_path.display()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(width: u32, height: u32, pixels: &[[u8; 4]]) -> Image {
+        Image {
+            width,
+            height,
+            data: pixels.concat(),
+        }
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let a = image(2, 1, &[[10, 20, 30, 255], [0, 0, 0, 0]]);
+        assert!(diff_images(&a, &a.clone(), 0).is_none());
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let actual = image(2, 1, &[[10, 20, 30, 255], [0, 0, 0, 0]]);
+        let expected = image(2, 1, &[[12, 18, 30, 253], [2, 0, 0, 2]]);
+        assert!(diff_images(&actual, &expected, 2).is_none());
+        assert!(diff_images(&actual, &expected, 1).is_some());
+    }
+
+    #[test]
+    fn mismatches_are_counted_and_marked() {
+        let actual = image(3, 1, &[[0, 0, 0, 255], [0, 0, 0, 255], [0, 0, 0, 255]]);
+        let expected = image(3, 1, &[[0, 0, 0, 255], [9, 0, 0, 255], [0, 0, 0, 240]]);
+
+        let (mismatched, diff) = diff_images(&actual, &expected, 8).unwrap();
+        assert_eq!(mismatched, 2);
+        assert_eq!((diff.width, diff.height), (3, 1));
+        assert_ne!(diff.pixel(0, 0), [0xff, 0, 0, 0xff]);
+        assert_eq!(diff.pixel(1, 0), [0xff, 0, 0, 0xff]);
+        assert_eq!(diff.pixel(2, 0), [0xff, 0, 0, 0xff]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Image sizes differ")]
+    fn sizes_must_match() {
+        let pixels = [[0, 0, 0, 255]; 2];
+        diff_images(&image(2, 1, &pixels), &image(1, 2, &pixels), 0);
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use ab_glyph::{Font as _, GlyphId};
+use wgpu::{BindGroupLayout, Device, Queue, TextureFormat};
+
+use super::Font;
+use crate::texture::{texture_layout, TextureBinding};
+
+/// Size, in atlas texels, at which glyphs are rasterized. Since the atlas
+/// stores distances rather than coverage, glyphs stay sharp when drawn much
+/// larger than this.
+pub(crate) const RASTER_SIZE: f32 = 48.;
+
+/// Distance, in atlas texels, over which the distance field ramps from fully
+/// outside to fully inside. Glyphs are padded by this much on each side.
+const SPREAD: u32 = 6;
+
+const ATLAS_WIDTH: u32 = 1024;
+
+/// Where a glyph is in the atlas, and how its bitmap sits relative to the
+/// glyph origin on the baseline, in texels with Y increasing going down.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct AtlasGlyph {
+    pub uv_min: [f32; 2],
+    pub uv_max: [f32; 2],
+    pub offset: [f32; 2],
+    pub size: [f32; 2],
+}
+
+/// Signed distance fields of a set of glyphs, packed into one texture.
+pub(crate) struct GlyphAtlas {
+    glyphs: HashMap<GlyphId, AtlasGlyph>,
+    pub layout: BindGroupLayout,
+    pub texture: TextureBinding,
+}
+
+struct Bitmap {
+    width: u32,
+    height: u32,
+    offset: [f32; 2],
+    pixels: Vec<u8>,
+}
+
+/// Squared Euclidean distance transform of a row or column, in place, by the
+/// lower envelope of parabolas (Felzenszwalb and Huttenlocher). `f` holds 0
+/// at feature cells and a large value elsewhere.
+fn edt_1d(f: &mut [f32]) {
+    let n = f.len();
+    let mut v = vec![0usize; n];
+    let mut z = vec![0f32; n + 1];
+    let mut k = 0;
+    z[0] = f32::NEG_INFINITY;
+    z[1] = f32::INFINITY;
+
+    let intersection = |f: &[f32], q: usize, p: usize| {
+        ((f[q] + (q * q) as f32) - (f[p] + (p * p) as f32)) / (2 * q - 2 * p) as f32
+    };
+
+    for q in 1..n {
+        let mut s = intersection(f, q, v[k]);
+        while s <= z[k] {
+            k -= 1;
+            s = intersection(f, q, v[k]);
+        }
+        k += 1;
+        v[k] = q;
+        z[k] = s;
+        z[k + 1] = f32::INFINITY;
+    }
+
+    let source = f.to_vec();
+    k = 0;
+    for (q, d) in f.iter_mut().enumerate() {
+        while z[k + 1] < q as f32 {
+            k += 1;
+        }
+        let dq = q as f32 - v[k] as f32;
+        *d = dq * dq + source[v[k]];
+    }
+}
+
+/// Distance from each cell to the nearest cell where `feature` is true.
+fn distance_transform(width: usize, height: usize, feature: impl Fn(usize) -> bool) -> Vec<f32> {
+    // Larger than any squared distance within a glyph bitmap, but small
+    // enough that adding squared offsets to it stays finite.
+    const FAR: f32 = 1e10;
+
+    let mut grid: Vec<f32> = (0..width * height)
+        .map(|i| if feature(i) { 0. } else { FAR })
+        .collect();
+
+    let mut column = vec![0.; height];
+    for x in 0..width {
+        for y in 0..height {
+            column[y] = grid[y * width + x];
+        }
+        edt_1d(&mut column);
+        for y in 0..height {
+            grid[y * width + x] = column[y];
+        }
+    }
+    for row in grid.chunks_mut(width) {
+        edt_1d(row);
+    }
+
+    grid.iter().map(|d| d.sqrt()).collect()
+}
+
+fn rasterize(font: &Font, id: GlyphId) -> Option<Bitmap> {
+    let outlined = font.font.outline_glyph(id.with_scale(RASTER_SIZE))?;
+    let bounds = outlined.px_bounds();
+    let (glyph_width, glyph_height) = (bounds.width() as u32, bounds.height() as u32);
+    let width = (glyph_width + 2 * SPREAD) as usize;
+    let height = (glyph_height + 2 * SPREAD) as usize;
+
+    let mut coverage = vec![0f32; width * height];
+    outlined.draw(|x, y, c| {
+        let index = (y + SPREAD) as usize * width + (x + SPREAD) as usize;
+        coverage[index] = c;
+    });
+
+    let inside = |i: usize| coverage[i] >= 0.5;
+    let to_inside = distance_transform(width, height, inside);
+    let to_outside = distance_transform(width, height, |i| !inside(i));
+
+    // Map signed distances, positive inside, so that the edge is at 0.5.
+    let pixels = to_outside
+        .iter()
+        .zip(&to_inside)
+        .map(|(outside, inside)| {
+            let d = outside - inside;
+            ((0.5 + d / (2. * SPREAD as f32)).clamp(0., 1.) * 255.).round() as u8
+        })
+        .collect();
+
+    Some(Bitmap {
+        width: width as u32,
+        height: height as u32,
+        offset: [bounds.min.x - SPREAD as f32, bounds.min.y - SPREAD as f32],
+        pixels,
+    })
+}
+
+impl GlyphAtlas {
+    /// Rasterizes the glyphs that `font` uses for `chars`, and uploads them
+    /// to a new texture. Glyphs that would make the texture taller than the
+    /// device allows are left out.
+    pub fn new(
+        device: &Device,
+        queue: &Queue,
+        font: &Font,
+        chars: impl Iterator<Item = char>,
+    ) -> Self {
+        let mut ids: Vec<GlyphId> = chars.map(|c| font.font.glyph_id(c)).collect();
+        ids.sort_by_key(|id| id.0);
+        ids.dedup();
+
+        // Pack bitmaps into rows, left to right, starting a new row when one
+        // doesn't fit.
+        let max_height = device.limits().max_texture_dimension_2d;
+        let mut placed = Vec::new();
+        let (mut x, mut y, mut row_height) = (0, 0, 0);
+        for id in ids {
+            let bitmap = match rasterize(font, id) {
+                Some(bitmap) => bitmap,
+                None => continue,
+            };
+            if x + bitmap.width > ATLAS_WIDTH {
+                x = 0;
+                y += row_height;
+                row_height = 0;
+            }
+            if y + bitmap.height > max_height {
+                continue;
+            }
+            let (width, height) = (bitmap.width, bitmap.height);
+            placed.push((id, x, y, bitmap));
+            x += width;
+            row_height = row_height.max(height);
+        }
+        let height = (y + row_height).max(1);
+
+        let mut pixels = vec![0u8; (ATLAS_WIDTH * height) as usize];
+        let mut glyphs = HashMap::new();
+        for (id, x, y, bitmap) in placed {
+            for row in 0..bitmap.height {
+                let start = ((y + row) * ATLAS_WIDTH + x) as usize;
+                let source = (row * bitmap.width) as usize;
+                pixels[start..start + bitmap.width as usize]
+                    .copy_from_slice(&bitmap.pixels[source..source + bitmap.width as usize]);
+            }
+
+            glyphs.insert(
+                id,
+                AtlasGlyph {
+                    uv_min: [x as f32 / ATLAS_WIDTH as f32, y as f32 / height as f32],
+                    uv_max: [
+                        (x + bitmap.width) as f32 / ATLAS_WIDTH as f32,
+                        (y + bitmap.height) as f32 / height as f32,
+                    ],
+                    offset: bitmap.offset,
+                    size: [bitmap.width as f32, bitmap.height as f32],
+                },
+            );
+        }
+
+        let layout = texture_layout(device);
+        let texture = TextureBinding::new(
+            device,
+            queue,
+            &layout,
+            TextureFormat::R8Unorm,
+            1,
+            (ATLAS_WIDTH, height),
+            &pixels,
+        );
+
+        GlyphAtlas {
+            glyphs,
+            layout,
+            texture,
+        }
+    }
+
+    pub fn get(&self, id: GlyphId) -> Option<&AtlasGlyph> {
+        self.glyphs.get(&id)
+    }
+}
@@ -0,0 +1,546 @@
+use crate::layer::{DrawContext, DrawState, Drawable, Layer, UpdateState};
+use crate::{GenericDrawable, GenericLayer};
+
+use crate::color::Color;
+use crate::error::Error;
+use crate::export::ShapeVisitor;
+use crate::gpu_data::{GpuBuffer, GpuSerializable};
+use ab_glyph::{point, Font as _, FontArc, GlyphId, OutlineCurve, Point, ScaleFont};
+use atlas::{GlyphAtlas, RASTER_SIZE};
+use std::borrow::Cow;
+use std::path::Path;
+use wgpu::{
+    BlendComponent, BlendState, RenderPipeline, ShaderModuleDescriptor, ShaderSource,
+    VertexBufferLayout,
+};
+use winit::dpi::PhysicalSize;
+
+mod atlas;
+
+/// A TrueType or OpenType font.
+#[derive(Clone)]
+pub struct Font {
+    font: FontArc,
+    /// The family name from the font's name table, if it has one.
+    family: Option<String>,
+}
+
+impl Font {
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, Error> {
+        let family = family_name(&data);
+        Ok(Font {
+            font: FontArc::try_from_vec(data)?,
+            family,
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// The size of the font's em square when drawn with the given line
+    /// height, in the same units.
+    pub(crate) fn em_size(&self, size: f32) -> f32 {
+        let units_per_em = self.font.units_per_em().unwrap_or(1000.);
+        units_per_em * self.font.as_scaled(size).v_scale_factor()
+    }
+
+    pub(crate) fn family(&self) -> Option<&str> {
+        self.family.as_deref()
+    }
+}
+
+/// The font's typographic family name, or failing that its legacy family
+/// name, which only covers four styles per family.
+///
+/// ab_glyph doesn't expose the font's name table, so this reads it directly.
+/// Only Unicode and Windows platform names are decoded, both of which are
+/// UTF-16, and of a collection only the first font is read.
+fn family_name(data: &[u8]) -> Option<String> {
+    const TYPOGRAPHIC_FAMILY: u16 = 16;
+    const FAMILY: u16 = 1;
+
+    let u16_at = |offset: usize| {
+        let bytes = data.get(offset..offset + 2)?;
+        Some(u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    let u32_at = |offset: usize| {
+        let bytes = data.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+    };
+
+    let font = if data.get(..4)? == b"ttcf" {
+        u32_at(12)?
+    } else {
+        0
+    };
+    let name = (0..u16_at(font + 4)?)
+        .map(|i| font + 12 + 16 * i)
+        .find(|&record| data.get(record..record + 4) == Some(b"name"))?;
+    let name = u32_at(name + 8)?;
+    let count = u16_at(name + 2)?;
+    let strings = name + u16_at(name + 4)?;
+
+    [TYPOGRAPHIC_FAMILY, FAMILY].iter().find_map(|&id| {
+        (0..count).map(|i| name + 6 + 12 * i).find_map(|record| {
+            let platform = u16_at(record)?;
+            if (platform != 0 && platform != 3) || u16_at(record + 6)? != id as usize {
+                return None;
+            }
+            let start = strings + u16_at(record + 10)?;
+            let bytes = data.get(start..start + u16_at(record + 8)?)?;
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        })
+    })
+}
+
+/// Which point of a block of text is placed at its `position`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far the anchor is across the text, and down it, from 0 to 1.
+    pub(crate) fn fractions(self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0., 0.),
+            Anchor::Top => (0.5, 0.),
+            Anchor::TopRight => (1., 0.),
+            Anchor::Left => (0., 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1., 0.5),
+            Anchor::BottomLeft => (0., 1.),
+            Anchor::Bottom => (0.5, 1.),
+            Anchor::BottomRight => (1., 1.),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Text {
+    /// The text to draw. Lines are separated by `\n`, and each is aligned
+    /// horizontally according to `anchor`.
+    pub text: String,
+    pub position: [f32; 2],
+    /// Height of a line, from the font's ascent to its descent, in scene
+    /// units.
+    pub size: f32,
+    pub color: Color,
+    pub anchor: Anchor,
+}
+
+/// A line of a `Text`, laid out.
+pub(crate) struct TextLine<'a> {
+    pub text: &'a str,
+    /// Point on the baseline that the line is aligned to horizontally.
+    pub origin: [f32; 2],
+    pub width: f32,
+}
+
+impl Text {
+    pub(crate) fn lines(&self, font: &Font) -> Vec<TextLine<'_>> {
+        let scaled = font.font.as_scaled(self.size);
+        let (_, fy) = self.anchor.fractions();
+
+        let lines: Vec<&str> = self.text.split('\n').collect();
+        let line_height = scaled.height() + scaled.line_gap();
+        let block_height = line_height * lines.len() as f32 - scaled.line_gap();
+        let top = self.position[1] + fy * block_height;
+
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let mut width = 0.;
+                let mut previous = None;
+                for c in text.chars() {
+                    let id = scaled.glyph_id(c);
+                    if let Some(previous) = previous {
+                        width += scaled.kern(previous, id);
+                    }
+                    width += scaled.h_advance(id);
+                    previous = Some(id);
+                }
+
+                TextLine {
+                    text,
+                    origin: [
+                        self.position[0],
+                        top - scaled.ascent() - line_height * i as f32,
+                    ],
+                    width,
+                }
+            })
+            .collect()
+    }
+
+    /// Each glyph of the text, with the point on the baseline where it is
+    /// drawn from.
+    pub(crate) fn glyphs(&self, font: &Font) -> Vec<(GlyphId, [f32; 2])> {
+        let scaled = font.font.as_scaled(self.size);
+        let (fx, _) = self.anchor.fractions();
+
+        let mut glyphs = Vec::new();
+        for line in self.lines(font) {
+            let [x, baseline] = line.origin;
+            let mut caret = x - fx * line.width;
+            let mut previous = None;
+            for c in line.text.chars() {
+                let id = scaled.glyph_id(c);
+                if let Some(previous) = previous {
+                    caret += scaled.kern(previous, id);
+                }
+                glyphs.push((id, [caret, baseline]));
+                caret += scaled.h_advance(id);
+                previous = Some(id);
+            }
+        }
+        glyphs
+    }
+
+    /// The outlines of the text's glyphs, in scene coordinates.
+    pub(crate) fn outline(&self, font: &Font) -> Vec<OutlineCurve> {
+        let scaled = font.font.as_scaled(self.size);
+        let (sx, sy) = (scaled.h_scale_factor(), scaled.v_scale_factor());
+
+        let mut curves = Vec::new();
+        for (id, [x, y]) in self.glyphs(font) {
+            let outline = match font.font.outline(id) {
+                Some(outline) => outline,
+                None => continue,
+            };
+            // Font units have Y increasing going up, like the scene.
+            let place = |p: Point| point(x + p.x * sx, y + p.y * sy);
+            curves.extend(outline.curves.into_iter().map(|curve| match curve {
+                OutlineCurve::Line(a, b) => OutlineCurve::Line(place(a), place(b)),
+                OutlineCurve::Quad(a, b, c) => OutlineCurve::Quad(place(a), place(b), place(c)),
+                OutlineCurve::Cubic(a, b, c, d) => {
+                    OutlineCurve::Cubic(place(a), place(b), place(c), place(d))
+                }
+            }));
+        }
+        curves
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Zeroable, bytemuck::Pod)]
+pub(crate) struct GlyphInstance {
+    upper_left: [f32; 2],
+    lower_right: [f32; 2],
+    uv_min: [f32; 2],
+    uv_max: [f32; 2],
+    color: Color,
+}
+
+impl GpuSerializable for GlyphInstance {
+    fn gpu_serialize(data: &[Self]) -> &[u8] {
+        bytemuck::cast_slice(data)
+    }
+
+    fn buffer_layout<'a>() -> VertexBufferLayout<'a> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<GlyphInstance>() as wgpu::BufferAddress,
+            step_mode: wgpu::InputStepMode::Instance,
+            attributes: &[
+                wgpu::VertexAttribute {
+                    offset: 0,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 4]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 6]>() as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float32x2,
+                },
+                wgpu::VertexAttribute {
+                    offset: std::mem::size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
+            ],
+        }
+    }
+}
+
+/// Lays out `data` into one quad per visible glyph. Glyphs missing from the
+/// atlas are skipped, but still advance the text.
+fn glyph_instances(font: &Font, atlas: &GlyphAtlas, data: &[Text]) -> Vec<GlyphInstance> {
+    let mut instances = Vec::new();
+    for text in data {
+        let scale = text.size / RASTER_SIZE;
+
+        for (id, [x, baseline]) in text.glyphs(font) {
+            if let Some(glyph) = atlas.get(id) {
+                // Atlas offsets have Y increasing going down, unlike the
+                // scene.
+                let upper_left = [
+                    x + glyph.offset[0] * scale,
+                    baseline - glyph.offset[1] * scale,
+                ];
+                instances.push(GlyphInstance {
+                    upper_left,
+                    lower_right: [
+                        upper_left[0] + glyph.size[0] * scale,
+                        upper_left[1] - glyph.size[1] * scale,
+                    ],
+                    uv_min: glyph.uv_min,
+                    uv_max: glyph.uv_max,
+                    color: text.color,
+                });
+            }
+        }
+    }
+    instances
+}
+
+pub struct TextLayer {
+    font: Font,
+    data: Vec<Text>,
+    extra_chars: String,
+}
+
+impl TextLayer {
+    pub fn new(font: Font, data: Vec<Text>) -> Self {
+        TextLayer {
+            font,
+            data,
+            extra_chars: String::new(),
+        }
+    }
+
+    /// Adds the glyphs for `chars` to the atlas along with those of the
+    /// layer's text, so that `TextLayerDrawable::set_texts` can draw them.
+    pub fn with_chars(mut self, chars: &str) -> Self {
+        self.extra_chars.push_str(chars);
+        self
+    }
+}
+
+/// Draws text from a signed distance field atlas of its glyphs, which stays
+/// sharp at any zoom.
+pub struct TextLayerDrawable {
+    render_pipeline: RenderPipeline,
+    font: Font,
+    atlas: GlyphAtlas,
+    instance_buffer: GpuBuffer<GlyphInstance>,
+}
+
+impl TextLayerDrawable {
+    /// Replaces the text drawn by this drawable. Only glyphs that were in
+    /// the atlas when the drawable was created are drawn; see
+    /// `TextLayer::with_chars`.
+    pub fn set_texts(&mut self, data: &[Text], update_state: &UpdateState) {
+        self.instance_buffer.update(
+            &glyph_instances(&self.font, &self.atlas, data),
+            update_state.device,
+            update_state.encoder.borrow_mut(),
+        );
+    }
+}
+
+impl Drawable for TextLayerDrawable {
+    fn draw<'a>(&'a self, draw_state: &DrawState<'a, '_>) {
+        let mut render_pass = draw_state.render_pass.borrow_mut();
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, draw_state.bind_group, &[]);
+        render_pass.set_bind_group(1, &self.atlas.texture.bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.instance_buffer.all());
+        render_pass.draw(0..6, 0..self.instance_buffer.len());
+    }
+}
+
+impl GenericLayer for TextLayer {
+    fn init_drawable_generic(&self, draw_context: &DrawContext) -> crate::GenericDrawable {
+        GenericDrawable::new(self.init_drawable(draw_context))
+    }
+
+    fn visit_shapes_generic(&self, size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        self.visit_shapes(size, visitor);
+    }
+}
+
+impl Layer for TextLayer {
+    type D = TextLayerDrawable;
+
+    fn init_drawable(&self, draw_context: &DrawContext) -> TextLayerDrawable {
+        let DrawContext {
+            device,
+            queue,
+            sc_desc,
+            transform_layout,
+            sample_count,
+        } = *draw_context;
+
+        let chars = self
+            .data
+            .iter()
+            .flat_map(|text| text.text.chars())
+            .chain(self.extra_chars.chars());
+        let atlas = GlyphAtlas::new(device, queue, &self.font, chars);
+        let instance_buffer =
+            GpuBuffer::new(&glyph_instances(&self.font, &atlas, &self.data), device);
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Render Pipeline Layout"),
+                bind_group_layouts: &[transform_layout, &atlas.layout],
+                push_constant_ranges: &[],
+            });
+
+        let shader_module = device.create_shader_module(&ShaderModuleDescriptor {
+            label: None,
+            source: ShaderSource::Wgsl(Cow::Borrowed(include_str!("shader.wgsl"))),
+            flags: Default::default(),
+        });
+
+        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Render Pipeline"),
+            layout: Some(&render_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader_module,
+                entry_point: "vs_main",
+                buffers: &[GlyphInstance::buffer_layout()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader_module,
+                entry_point: "fs_main",
+                targets: &[wgpu::ColorTargetState {
+                    format: sc_desc.format,
+                    write_mask: wgpu::ColorWrite::ALL,
+                    blend: Some(BlendState {
+                        color: BlendComponent::OVER,
+                        alpha: BlendComponent::REPLACE,
+                    }),
+                }],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+                clamp_depth: false,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: sample_count,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+        });
+
+        TextLayerDrawable {
+            render_pipeline,
+            font: self.font.clone(),
+            atlas,
+            instance_buffer,
+        }
+    }
+
+    fn visit_shapes(&self, _size: PhysicalSize<u32>, visitor: &mut dyn ShapeVisitor) {
+        visitor.texts(&self.data, &self.font);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::family_name;
+
+    /// Builds a font containing only a name table with the given platform,
+    /// name ID and name of each record.
+    fn font(records: &[(u16, u16, &str)]) -> Vec<u8> {
+        let mut strings = Vec::new();
+        let mut name = Vec::new();
+        name.extend_from_slice(&0u16.to_be_bytes());
+        name.extend_from_slice(&(records.len() as u16).to_be_bytes());
+        name.extend_from_slice(&(6 + 12 * records.len() as u16).to_be_bytes());
+        for &(platform, id, text) in records {
+            let encoded: Vec<u8> = if platform == 1 {
+                text.bytes().collect()
+            } else {
+                text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+            };
+            for field in [
+                platform,
+                1,
+                0,
+                id,
+                encoded.len() as u16,
+                strings.len() as u16,
+            ] {
+                name.extend_from_slice(&field.to_be_bytes());
+            }
+            strings.extend(encoded);
+        }
+        name.extend(strings);
+
+        let mut data = vec![0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0];
+        data.extend_from_slice(b"name");
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&28u32.to_be_bytes());
+        data.extend_from_slice(&(name.len() as u32).to_be_bytes());
+        data.extend(name);
+        data
+    }
+
+    #[test]
+    fn typographic_family_preferred() {
+        let data = font(&[(3, 1, "Sans Light"), (3, 16, "Sans")]);
+        assert_eq!(family_name(&data).as_deref(), Some("Sans"));
+    }
+
+    #[test]
+    fn legacy_family_fallback() {
+        let data = font(&[(3, 2, "Bold"), (0, 1, "Sans Ü")]);
+        assert_eq!(family_name(&data).as_deref(), Some("Sans Ü"));
+    }
+
+    #[test]
+    fn mac_names_ignored() {
+        assert_eq!(family_name(&font(&[(1, 1, "Sans")])), None);
+    }
+
+    #[test]
+    fn collection_reads_first_font() {
+        let mut data = b"ttcf\0\x01\0\0\0\0\0\x01\0\0\0\x10".to_vec();
+        data.extend(font(&[(3, 1, "Sans")]));
+        // The font's table offsets are from the start of the file.
+        data[16 + 12 + 8 + 3] += 16;
+        assert_eq!(family_name(&data).as_deref(), Some("Sans"));
+    }
+
+    #[test]
+    fn truncated_data() {
+        let data = font(&[(3, 1, "Sans")]);
+        for length in 0..data.len() {
+            assert_eq!(family_name(&data[..length]), None);
+        }
+    }
+}
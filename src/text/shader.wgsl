@@ -0,0 +1,62 @@
+struct VertexOutput {
+    [[location(0)]] color: vec4<f32>;
+    [[location(1)]] uv: vec2<f32>;
+    [[builtin(position)]] position: vec4<f32>;
+};
+
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+[[group(1), binding(0)]]
+var atlas: texture_2d<f32>;
+
+[[group(1), binding(1)]]
+var atlas_sampler: sampler;
+
+let corners: array<vec2<f32>, 6> = array<vec2<f32>, 6>(
+    vec2<f32>(0., 0.),
+    vec2<f32>(1., 0.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., 0.),
+    vec2<f32>(0., 1.),
+    vec2<f32>(1., 1.),
+);
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] in_vertex_index: u32,
+    [[location(0)]] upper_left: vec2<f32>,
+    [[location(1)]] lower_right: vec2<f32>,
+    [[location(2)]] uv_min: vec2<f32>,
+    [[location(3)]] uv_max: vec2<f32>,
+    [[location(4)]] color: vec4<f32>,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    let corner: vec2<f32> = corners[in_vertex_index];
+
+    out.color = color;
+    out.uv = mix(uv_min, uv_max, corner);
+    out.position = uniforms.transform * vec4<f32>(mix(upper_left, lower_right, corner), 0., 1.);
+
+    return out;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    // The atlas stores signed distance to the glyph outline, with the edge
+    // at 0.5. Dividing by its screen-space rate of change gives the distance
+    // in pixels, so the edge is anti-aliased over one pixel at any zoom.
+    let d: f32 = textureSample(atlas, atlas_sampler, in.uv).r - 0.5;
+    let alpha: f32 = clamp(0.5 + d / fwidth(d), 0., 1.);
+
+    if (alpha < 0.01) {
+        discard;
+    }
+
+    return in.color * alpha;
+}
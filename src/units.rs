@@ -0,0 +1,23 @@
+/// Units of the stroke widths in a layer.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum WidthUnits {
+    /// Widths scale with the scene when zooming.
+    #[default]
+    Scene,
+    /// Widths stay the same number of screen pixels at every zoom level and
+    /// window size.
+    Pixels,
+}
+
+impl WidthUnits {
+    /// Prepends the uniforms, `pixel_size()` and `width_scale()` to the
+    /// source of a stroke shader. `width_scale()` returns scene units per
+    /// unit of width.
+    pub(crate) fn shader_source(self, source: &str) -> String {
+        let width_scale = match self {
+            WidthUnits::Scene => "fn width_scale() -> f32 {\n    return 1.;\n}\n\n",
+            WidthUnits::Pixels => "fn width_scale() -> f32 {\n    return pixel_size();\n}\n\n",
+        };
+        [include_str!("units.wgsl"), "\n", width_scale, source].concat()
+    }
+}
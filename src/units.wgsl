@@ -0,0 +1,15 @@
+[[block]]
+struct Uniforms {
+    transform: mat4x4<f32>;
+    // Size of the render target in pixels.
+    viewport: vec2<f32>;
+};
+
+[[group(0), binding(0)]]
+var uniforms: Uniforms;
+
+// Scene units per pixel.
+fn pixel_size() -> f32 {
+    let scale: vec4<f32> = uniforms.transform * vec4<f32>(1., 0., 0., 0.);
+    return 2. / (scale.x * uniforms.viewport.x);
+}
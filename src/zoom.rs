@@ -6,6 +6,14 @@ use winit::window::{CursorIcon, Window};
 
 pub type Mat4 = [f32; 16];
 
+#[rustfmt::skip]
+pub const IDENTITY: Mat4 = [
+    1., 0., 0., 0.,
+    0., 1., 0., 0.,
+    0., 0., 1., 0.,
+    0., 0., 0., 1.,
+];
+
 const ZOOM_FACTOR: f32 = 1.001;
 
 fn size_to_vec(size: PhysicalSize<u32>) -> Vector2<f32> {
@@ -57,6 +65,39 @@ impl GpuCoordinate {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub center: [f32; 2],
+    pub scale: f32,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            center: [0., 0.],
+            scale: 1.,
+        }
+    }
+}
+
+impl Camera {
+    pub fn matrix(&self, size: PhysicalSize<u32>) -> Mat4 {
+        let x_x = self.scale / size.width as f32;
+        let y_y = self.scale / size.height as f32;
+        let x_w = x_x * -self.center[0];
+        let y_w = y_y * -self.center[1];
+
+        #[rustfmt::skip]
+        let matrix = [
+            x_x,  0., 0., 0.,
+             0., y_y, 0., 0.,
+             0.,  0., 1., 0.,
+            x_w, y_w, 0., 1.,
+        ];
+        matrix
+    }
+}
+
 pub struct ZoomState {
     center: SceneCoordinate,
     scale: Vector2<f32>,
@@ -76,6 +117,21 @@ impl ZoomState {
         }
     }
 
+    pub fn with_camera(size: PhysicalSize<u32>, camera: Camera) -> Self {
+        ZoomState {
+            center: SceneCoordinate(Vector2::from(camera.center)),
+            scale: Vector2::new(camera.scale, camera.scale),
+            ..ZoomState::new(size)
+        }
+    }
+
+    pub fn camera(&self) -> Camera {
+        Camera {
+            center: self.center.0.into(),
+            scale: self.scale[0],
+        }
+    }
+
     pub fn set_size(&mut self, size: PhysicalSize<u32>) {
         self.size = size;
     }
@@ -146,17 +202,6 @@ impl ZoomState {
     }
 
     pub fn matrix(&self) -> Mat4 {
-        let x_x = self.scale[0] / self.size.width as f32;
-        let y_y = self.scale[1] / self.size.height as f32;
-        let x_w = x_x * -self.center.0[0] as f32;
-        let y_w = y_y * -self.center.0[1] as f32;
-
-        #[cfg_attr(rustfmt, rustfmt_skip)]
-        [
-            x_x,  0., 0., 0.,
-             0., y_y, 0., 0.,
-             0.,  0., 1., 0.,
-            x_w, y_w, 0., 1.,
-        ]
+        self.camera().matrix(self.size)
     }
 }
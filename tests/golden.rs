@@ -0,0 +1,81 @@
+use vecdraw::testing::GoldenTest;
+use vecdraw::{Circle, CirclesLayer, Line, LineCap, LinesLayer, Rectangle, RectanglesLayer};
+
+fn golden() -> GoldenTest {
+    GoldenTest {
+        width: 64,
+        height: 64,
+        // Allow for differences in how GPUs compute derivatives, which
+        // decide the shading of anti-aliased edges.
+        tolerance: 8,
+        ..Default::default()
+    }
+}
+
+#[test]
+#[ignore = "needs a graphics adapter; run with --ignored"]
+fn circles() {
+    let layer = CirclesLayer::new(vec![
+        Circle {
+            position: [-24., 24.],
+            radius: 20.,
+            color: palette::named::PURPLE.into(),
+            ..Default::default()
+        },
+        Circle {
+            position: [20., -20.],
+            radius: 24.,
+            color: palette::named::GOLD.into(),
+            stroke_width: 4.,
+            stroke_color: palette::named::STEELBLUE.into(),
+        },
+    ]);
+
+    golden().assert_matches(&layer, "tests/golden/circles.png");
+}
+
+#[test]
+#[ignore = "needs a graphics adapter; run with --ignored"]
+fn lines() {
+    let layer = LinesLayer::new(vec![
+        Line {
+            start: [-40., -32.],
+            end: [40., -32.],
+            color: palette::named::SEAGREEN.into(),
+            width: 8.,
+        },
+        Line {
+            start: [-36., 0.],
+            end: [36., 40.],
+            color: palette::named::CRIMSON.into(),
+            width: 5.,
+        },
+    ])
+    .with_cap(LineCap::Round);
+
+    golden().assert_matches(&layer, "tests/golden/lines.png");
+}
+
+#[test]
+#[ignore = "needs a graphics adapter; run with --ignored"]
+fn rectangles() {
+    let layer = RectanglesLayer::new(vec![
+        Rectangle {
+            upper_left: [-56., 48.],
+            bottom_right: [-8., 8.],
+            color: palette::named::SEAGREEN.into(),
+            ..Default::default()
+        },
+        Rectangle {
+            upper_left: [8., -8.],
+            bottom_right: [56., -48.],
+            color: palette::named::DARKORANGE.into(),
+            corner_radius: 8.,
+            stroke_width: 4.,
+            stroke_color: palette::named::BLACK.into(),
+            ..Default::default()
+        },
+    ]);
+
+    golden().assert_matches(&layer, "tests/golden/rectangles.png");
+}